@@ -0,0 +1,253 @@
+//! **rust_wasm_dodrio_templating_derive**
+//! The attribute macro `#[html_templating]` writes the big match blocks
+//! of the trait HtmlTemplating instead of the programmer.
+//! The methods of the RootRenderingComponent are annotated with the marker names
+//! and the macro generates replace_with_string(), retain_next_node_or_attribute(),
+//! replace_with_nodes(), set_event_listener() and marker_names().
+//! Duplicate names and names with the wrong prefix are compile errors.
+//! The generated code uses dodrio and web_sys re-exported from ::rust_wasm_dodrio_templating.
+
+// region: use
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, ImplItem, ImplItemFn, ItemImpl, LitStr};
+// endregion: use
+
+/// The kinds of markers and the prefix of their names.
+#[derive(Clone, Copy, PartialEq)]
+enum MarkerKind {
+    /// wt_ replace_with_string()
+    Text,
+    /// wb_ retain_next_node_or_attribute()
+    Boolean,
+    /// wn_ replace_with_nodes()
+    Nodes,
+    /// wl_ set_event_listener()
+    Listener,
+}
+
+impl MarkerKind {
+    fn from_attribute(attr: &Attribute) -> Option<MarkerKind> {
+        let ident = attr.path().get_ident()?;
+        match ident.to_string().as_str() {
+            "wt" => Some(MarkerKind::Text),
+            "wb" => Some(MarkerKind::Boolean),
+            "wn" => Some(MarkerKind::Nodes),
+            "wl" => Some(MarkerKind::Listener),
+            _ => None,
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            MarkerKind::Text => "wt_",
+            MarkerKind::Boolean => "wb_",
+            MarkerKind::Nodes => "wn_",
+            MarkerKind::Listener => "wl_",
+        }
+    }
+}
+
+/// One marker name mapped to one method.
+struct Marker {
+    kind: MarkerKind,
+    name: String,
+    method: syn::Ident,
+    span: Span,
+}
+
+/// Use it on the implementation of the trait HtmlTemplating.
+/// The methods annotated with `#[wt]`, `#[wb]`, `#[wn]` and `#[wl]` are moved into
+/// an inherent impl and the trait methods are generated.
+/// Without arguments the marker name is the prefix and the method name:
+/// `#[wt] fn nick_name()` is the marker `wt_nick_name`.
+/// If the method name starts with the prefix, it is the marker name: `#[wt] fn wt_nick_name()`.
+/// With the argument the name is explicit: `#[wt("wt_nick_name")]`.
+/// The other methods like repeat_count() stay in the trait implementation.
+///
+/// ```ignore
+/// #[html_templating]
+/// impl HtmlTemplating for RootRenderingComponent {
+///     #[wt]
+///     fn nick_name(&self) -> String { self.nick_name.clone() }
+///     #[wb("wb_is_visible")]
+///     fn is_visible(&self) -> bool { true }
+///     #[wn]
+///     fn items<'a>(&self, cx: &mut RenderContext<'a>) -> Vec<Node<'a>> { vec![] }
+///     #[wl]
+///     fn click(root: &mut dyn RootRender, vdom: VdomWeak, event: web_sys::Event) {}
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn html_templating(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item_impl = parse_macro_input!(item as ItemImpl);
+    match expand(item_impl) {
+        Ok(token_stream) => token_stream.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(mut item_impl: ItemImpl) -> syn::Result<proc_macro2::TokenStream> {
+    if item_impl.trait_.is_none() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[html_templating] must be used on `impl HtmlTemplating for ...`",
+        ));
+    }
+    let mut markers: Vec<Marker> = vec![];
+    let mut marker_methods: Vec<ImplItemFn> = vec![];
+    let mut trait_items: Vec<ImplItem> = vec![];
+    for impl_item in std::mem::take(&mut item_impl.items) {
+        match impl_item {
+            ImplItem::Fn(mut method) => {
+                let method_markers = take_markers(&mut method)?;
+                if method_markers.is_empty() {
+                    trait_items.push(ImplItem::Fn(method));
+                } else {
+                    for marker in method_markers {
+                        if markers.iter().any(|x| x.name == marker.name) {
+                            return Err(syn::Error::new(
+                                marker.span,
+                                format!("duplicate marker name {}", marker.name),
+                            ));
+                        }
+                        markers.push(marker);
+                    }
+                    marker_methods.push(method);
+                }
+            }
+            other => trait_items.push(other),
+        }
+    }
+
+    let arms = |kind: MarkerKind| {
+        let names: Vec<&String> = markers
+            .iter()
+            .filter(|x| x.kind == kind)
+            .map(|x| &x.name)
+            .collect();
+        let methods: Vec<&syn::Ident> = markers
+            .iter()
+            .filter(|x| x.kind == kind)
+            .map(|x| &x.method)
+            .collect();
+        (names, methods)
+    };
+    let (text_names, text_methods) = arms(MarkerKind::Text);
+    let (boolean_names, boolean_methods) = arms(MarkerKind::Boolean);
+    let (nodes_names, nodes_methods) = arms(MarkerKind::Nodes);
+    let (listener_names, listener_methods) = arms(MarkerKind::Listener);
+    let all_names: Vec<&String> = markers.iter().map(|x| &x.name).collect();
+
+    item_impl.items = trait_items;
+    let generated: Vec<ImplItem> = vec![
+        syn::parse_quote! {
+            #[allow(unused_variables, clippy::match_single_binding)]
+            fn replace_with_string(&self, fn_name: &str) -> String {
+                match fn_name {
+                    #( #text_names => self.#text_methods(), )*
+                    _ => format!("Error: Unrecognized replace_with_string: \"{}\"", fn_name),
+                }
+            }
+        },
+        syn::parse_quote! {
+            #[allow(unused_variables, clippy::match_single_binding, clippy::extra_unused_lifetimes)]
+            fn retain_next_node_or_attribute<'a>(&self, fn_name: &str) -> bool {
+                match fn_name {
+                    #( #boolean_names => self.#boolean_methods(), )*
+                    _ => true,
+                }
+            }
+        },
+        syn::parse_quote! {
+            #[allow(unused_variables, clippy::match_single_binding)]
+            fn replace_with_nodes<'a>(
+                &self,
+                cx: &mut ::rust_wasm_dodrio_templating::dodrio::RenderContext<'a>,
+                fn_name: &str,
+            ) -> Vec<::rust_wasm_dodrio_templating::dodrio::Node<'a>> {
+                match fn_name {
+                    #( #nodes_names => self.#nodes_methods(cx), )*
+                    _ => vec![],
+                }
+            }
+        },
+        syn::parse_quote! {
+            #[allow(unused_variables, clippy::match_single_binding, clippy::type_complexity)]
+            fn set_event_listener(
+                &self,
+                fn_name: String,
+            ) -> Box<dyn Fn(&mut dyn ::rust_wasm_dodrio_templating::dodrio::RootRender, ::rust_wasm_dodrio_templating::dodrio::VdomWeak, ::rust_wasm_dodrio_templating::web_sys::Event) + 'static> {
+                match fn_name.as_str() {
+                    #( #listener_names => Box::new(Self::#listener_methods), )*
+                    _ => Box::new(|_root, _vdom, _event| {}),
+                }
+            }
+        },
+        syn::parse_quote! {
+            fn marker_names(&self) -> &'static [&'static str] {
+                Self::MARKER_NAMES
+            }
+        },
+    ];
+    item_impl.items.extend(generated);
+
+    let self_ty = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    // return
+    Ok(quote! {
+        impl #impl_generics #self_ty #where_clause {
+            /// all the marker names implemented with #[html_templating]
+            pub const MARKER_NAMES: &'static [&'static str] = &[ #( #all_names ),* ];
+            #( #marker_methods )*
+        }
+        #item_impl
+    })
+}
+
+/// removes the marker attributes from the method and returns the markers
+fn take_markers(method: &mut ImplItemFn) -> syn::Result<Vec<Marker>> {
+    let mut markers = vec![];
+    let mut other_attrs = vec![];
+    for attr in std::mem::take(&mut method.attrs) {
+        let kind = match MarkerKind::from_attribute(&attr) {
+            Some(kind) => kind,
+            None => {
+                other_attrs.push(attr);
+                continue;
+            }
+        };
+        let (name, span) = match &attr.meta {
+            syn::Meta::Path(_) => {
+                // the method name can already have the prefix like the generated marker stubs
+                let method_name = method.sig.ident.to_string();
+                let name = if method_name.starts_with(kind.prefix()) {
+                    method_name
+                } else {
+                    format!("{}{}", kind.prefix(), method_name)
+                };
+                (name, method.sig.ident.span())
+            }
+            _ => {
+                let lit: LitStr = attr.parse_args()?;
+                (lit.value(), lit.span())
+            }
+        };
+        if !name.starts_with(kind.prefix()) {
+            return Err(syn::Error::new(
+                span,
+                format!("marker name {} must start with {}", name, kind.prefix()),
+            ));
+        }
+        markers.push(Marker {
+            kind,
+            name,
+            method: method.sig.ident.clone(),
+            span,
+        });
+    }
+    method.attrs = other_attrs;
+    // return
+    Ok(markers)
+}
@@ -0,0 +1,134 @@
+//! **template_lint**
+//! Command line lint for the html templates.
+//! It lists all the markers with their position and compares them with the names
+//! from the manifest file or from the string literals and the marker attributes in the Rust code.
+//!
+//! Usage:
+//! template_lint [--manifest markers.txt] [--rust src] templates/index.html ...
+//! template_lint --stubs TraitName templates/index.html ...
+//!
+//! The exit code is 1 if there are malformed or unknown markers.
+//! With --stubs it prints the Rust trait with all the markers instead of the lint.
+
+// region: use
+use rust_wasm_dodrio_templating::marker_stubs_mod::generate_marker_trait;
+use rust_wasm_dodrio_templating::template_lint_mod::*;
+use std::path::{Path, PathBuf};
+// endregion: use
+
+fn main() {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            eprintln!(
+                "Usage: template_lint [--manifest markers.txt] [--rust src] templates/index.html ..."
+            );
+            eprintln!("       template_lint --stubs TraitName templates/index.html ...");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// returns true if the templates are ok
+fn run(args: Vec<String>) -> Result<bool, String> {
+    let mut manifest_files = vec![];
+    let mut rust_paths = vec![];
+    let mut template_files = vec![];
+    let mut stubs_trait_name = None;
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--manifest" => manifest_files.push(iter.next().ok_or("--manifest needs a file")?),
+            "--rust" => rust_paths.push(iter.next().ok_or("--rust needs a file or folder")?),
+            "--stubs" => stubs_trait_name = Some(iter.next().ok_or("--stubs needs a trait name")?),
+            _ => template_files.push(arg),
+        }
+    }
+    if template_files.is_empty() {
+        return Err("no template files".to_owned());
+    }
+    if let Some(trait_name) = stubs_trait_name {
+        let mut templates = vec![];
+        for template_file in &template_files {
+            templates.push((template_file.as_str(), read_file(Path::new(template_file))?));
+        }
+        let templates: Vec<(&str, &str)> = templates
+            .iter()
+            .map(|(name, html_template)| (*name, html_template.as_str()))
+            .collect();
+        return match generate_marker_trait(&trait_name, &templates) {
+            Ok(code) => {
+                print!("{}", code);
+                Ok(true)
+            }
+            Err(err) => {
+                println!("malformed: {}", err);
+                Ok(false)
+            }
+        };
+    }
+
+    let mut is_ok = true;
+    let mut markers = vec![];
+    println!("Markers:");
+    for template_file in &template_files {
+        let lint = lint_template(template_file, &read_file(Path::new(template_file))?);
+        for marker in &lint.markers {
+            println!("  {}", marker);
+        }
+        for err in &lint.errors {
+            println!("malformed: {}", err);
+            is_ok = false;
+        }
+        markers.extend(lint.markers);
+    }
+
+    // without the known names only the list and the malformed markers
+    if manifest_files.is_empty() && rust_paths.is_empty() {
+        return Ok(is_ok);
+    }
+    let mut known_names = vec![];
+    for manifest_file in &manifest_files {
+        known_names.extend(names_in_manifest(&read_file(Path::new(manifest_file))?));
+    }
+    let mut rust_files = vec![];
+    for rust_path in &rust_paths {
+        collect_rust_files(Path::new(rust_path), &mut rust_files)?;
+    }
+    for rust_file in &rust_files {
+        known_names.extend(names_in_rust_code(&read_file(rust_file)?));
+    }
+    for marker in unknown_markers(&markers, &known_names) {
+        println!("unknown: {}", marker);
+        is_ok = false;
+    }
+    let mut unused = unused_names(&markers, &known_names);
+    unused.sort_unstable();
+    unused.dedup();
+    for name in unused {
+        println!("unused: {}", name);
+    }
+    // return
+    Ok(is_ok)
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path.display(), err))
+}
+
+/// the .rs file itself or all the .rs files in the folder and subfolders
+fn collect_rust_files(path: &Path, rust_files: &mut Vec<PathBuf>) -> Result<(), String> {
+    if path.is_dir() {
+        let entries = std::fs::read_dir(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+        for entry in entries {
+            let entry = entry.map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+            collect_rust_files(&entry.path(), rust_files)?;
+        }
+    } else if path.extension().is_some_and(|ext| ext == "rs") {
+        rust_files.push(path.to_path_buf());
+    }
+    Ok(())
+}
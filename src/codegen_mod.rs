@@ -0,0 +1,690 @@
+//! **codegen_mod**
+//! Rust code generator for html templates.
+//! It reads the same html templates that `render_template()` parses in runtime
+//! and creates the Rust code that builds the identical dodrio Node.
+//! It is meant to be used in the `build.rs` of the project, so the release build
+//! has nothing to parse in runtime.
+//! The dev build can still use the live-editable `render_template()`.
+
+// region: use
+use crate::html_entity_mod::EntityDecoding;
+use crate::html_template_mod::{HtmlOrSvg, ATTR_KEY};
+use crate::parsed_template_mod::*;
+use crate::template_error_mod::*;
+use crate::template_include_mod::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+use unwrap::unwrap;
+// endregion: use
+
+/// Generates the Rust code for a module with one function for every html template.
+/// The input is a slice of tuples (function name, html template).
+/// The result is ready to be written into a file in OUT_DIR and
+/// included in the project with `include!()`.
+pub fn generate_rust_code(templates: &[(&str, &str)]) -> Result<String, TemplateError> {
+    generate_rust_code_with_decoding(templates, EntityDecoding::default())
+}
+
+/// Generates the Rust code with the static text decoded as defined by entity_decoding.
+/// It must be the same as `HtmlTemplating::entity_decoding()`, that decodes the replaced strings in runtime.
+/// The include markers <!--wi_xxx--> and the layouts data-layout="xxx" are resolved
+/// with the templates in the same slice, where xxx is the function name.
+pub fn generate_rust_code_with_decoding(
+    templates: &[(&str, &str)],
+    entity_decoding: EntityDecoding,
+) -> Result<String, TemplateError> {
+    let template_source: HashMap<String, String> = templates
+        .iter()
+        .map(|(fn_name, html_template)| (fn_name.to_string(), html_template.to_string()))
+        .collect();
+    let mut code = String::new();
+    code.push_str("// Generated by rust_wasm_dodrio_templating::codegen_mod.\n");
+    code.push_str("// Do not edit. Change the html template and rebuild.\n");
+    for (fn_name, html_template) in templates {
+        code.push('\n');
+        code.push_str(&generate_template_fn_with_source(
+            fn_name,
+            html_template,
+            entity_decoding,
+            &template_source,
+        )?);
+    }
+    // return
+    Ok(code)
+}
+
+/// Helper for `build.rs`: reads the html template files,
+/// generates the Rust code and writes it in the `out_file`.
+/// The input is a slice of tuples (function name, path of the html template file).
+/// It prints `cargo:rerun-if-changed` for every template file.
+pub fn write_rust_code_for_files(
+    out_file: &std::path::Path,
+    template_files: &[(&str, &std::path::Path)],
+) -> Result<(), String> {
+    let mut templates = Vec::new();
+    for (fn_name, path) in template_files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let html_template = std::fs::read_to_string(path)
+            .map_err(|err| format!("Error: cannot read {}: {}", path.display(), err))?;
+        templates.push((*fn_name, html_template));
+    }
+    let templates: Vec<(&str, &str)> = templates
+        .iter()
+        .map(|(fn_name, html_template)| (*fn_name, html_template.as_str()))
+        .collect();
+    let code = generate_rust_code(&templates)?;
+    std::fs::write(out_file, code)
+        .map_err(|err| format!("Error: cannot write {}: {}", out_file.display(), err))
+}
+
+/// Generates the Rust code for one function that returns the root Node of the template.
+/// The generated function calls the methods of the trait HtmlTemplating
+/// on the same positions as `render_template()` does in runtime.
+/// Errors in the template are returned here, before compile time.
+pub fn generate_template_fn(fn_name: &str, html_template: &str) -> Result<String, TemplateError> {
+    generate_template_fn_with_decoding(fn_name, html_template, EntityDecoding::default())
+}
+
+/// Generates the Rust code for one function with the static text decoded as defined by entity_decoding.
+pub fn generate_template_fn_with_decoding(
+    fn_name: &str,
+    html_template: &str,
+    entity_decoding: EntityDecoding,
+) -> Result<String, TemplateError> {
+    generate_template_fn_with_source(fn_name, html_template, entity_decoding, &HashMap::new())
+}
+
+/// Generates the Rust code for one function with the layout and the includes
+/// resolved from the template_source.
+/// The layout and the included templates become part of the generated function.
+pub fn generate_template_fn_with_source(
+    fn_name: &str,
+    html_template: &str,
+    entity_decoding: EntityDecoding,
+    template_source: &dyn TemplateSource,
+) -> Result<String, TemplateError> {
+    let mut parsed_template =
+        ParsedTemplate::parse_with_decoding(fn_name, html_template, entity_decoding)?;
+    parsed_template.resolve_layout(fn_name, template_source, entity_decoding)?;
+    parsed_template.resolve_includes(fn_name, template_source, entity_decoding)?;
+    let root = &parsed_template.root;
+    let mut code = String::new();
+    unwrap!(writeln!(
+        code,
+        "#[allow(unused_mut, unused_variables, unused_assignments, clippy::all)]\n\
+        pub fn {}<'a, R: ::rust_wasm_dodrio_templating::html_template_mod::HtmlTemplating>(\n    \
+        rrc: &R,\n    \
+        cx: &mut ::rust_wasm_dodrio_templating::dodrio::RenderContext<'a>,\n\
+        ) -> Result<::rust_wasm_dodrio_templating::dodrio::Node<'a>, ::rust_wasm_dodrio_templating::template_error_mod::TemplateError> {{\n    \
+        let bump = cx.bump;",
+        fn_name
+    ));
+    // the root element is special
+    let html_or_svg_element = HtmlOrSvg::Html.for_element(&root.name);
+    let root_code = generate_element(
+        root,
+        html_or_svg_element,
+        html_or_svg_element.for_children(&root.name),
+        1,
+        None,
+        &mut vec![],
+    );
+    unwrap!(writeln!(code, "    Ok({})\n}}", root_code));
+    // return
+    Ok(code)
+}
+
+/// Recursive function that generates a block expression for one element.
+/// It follows the same rules as the walker in `render_target_mod`:
+/// replace_string, replace_vec_nodes, replace_boolean and replace_repeat are applied to the next node.
+/// The repeat vector contains the code of the RepeatItem for every repeat around this element.
+/// The parent_name is None for the root element, that has no siblings.
+#[allow(clippy::too_many_lines)]
+fn generate_element(
+    template_element: &TemplateElement,
+    html_or_svg_element: HtmlOrSvg,
+    html_or_svg_children: HtmlOrSvg,
+    depth: usize,
+    parent_name: Option<&str>,
+    repeat: &mut Vec<String>,
+) -> String {
+    let element = format!("element_{}", depth);
+    let indent = "    ".repeat(depth + 1);
+    let mut code = String::new();
+    let mut replace_string: Option<&str> = None;
+    let mut replace_vec_nodes: Option<&str> = None;
+    let mut replace_boolean: Option<&TemplateNode> = None;
+    let mut replace_repeat: Option<&str> = None;
+    let mut replace_boolean_attribute: Option<&str> = None;
+    // the chain of wb_, wb_else_if and wb_else needs a variable in runtime
+    let else_chain = format!("else_chain_{}", depth);
+    let has_else = template_element
+        .children
+        .iter()
+        .any(|node| matches!(node, TemplateNode::ElseIf(_) | TemplateNode::Else));
+
+    code.push_str("{\n");
+    unwrap!(writeln!(
+        code,
+        "{}let mut {} = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, {:?});",
+        indent, element, template_element.name
+    ));
+    if let Some(namespace) = html_or_svg_element.namespace_uri() {
+        unwrap!(writeln!(
+            code,
+            "{}{} = {}.namespace(Some({:?}));",
+            indent, element, element, namespace
+        ));
+    }
+    if has_else {
+        unwrap!(writeln!(code, "{}let mut {} = false;", indent, else_chain));
+    }
+    // the keys of the children are checked like in the walker
+    let has_keyed_children = template_element.children.iter().any(|node| match node {
+        TemplateNode::Element(child) => child.attributes.iter().any(|attribute| {
+            matches!(attribute, TemplateAttribute::Static { name, .. }
+                | TemplateAttribute::Interpolated { name, .. } if name == ATTR_KEY)
+        }),
+        _ => false,
+    });
+    if has_keyed_children {
+        unwrap!(writeln!(
+            code,
+            "{}let mut sibling_keys_{} = ::rust_wasm_dodrio_templating::html_template_mod::SiblingKeys::default();",
+            indent, depth
+        ));
+    }
+    // the classes from data-wc- are added to the class attribute
+    let toggle_classes = format!("toggle_classes_{}", depth);
+    let class_is_rendered = format!("class_is_rendered_{}", depth);
+    let has_toggle_classes = template_element
+        .attributes
+        .iter()
+        .any(|attribute| matches!(attribute, TemplateAttribute::ToggleClass { .. }));
+    if has_toggle_classes {
+        unwrap!(writeln!(
+            code,
+            "{}let mut {}: Vec<&str> = vec![];\n{}let mut {} = false;",
+            indent, toggle_classes, indent, class_is_rendered
+        ));
+        for attribute in &template_element.attributes {
+            if let TemplateAttribute::ToggleClass {
+                class_name,
+                fn_name,
+            } = attribute
+            {
+                unwrap!(writeln!(
+                    code,
+                    "{}if {} {{ {}.push({:?}); }}",
+                    indent,
+                    method_call_code(
+                        "retain_next_node_or_attribute",
+                        &format!("{:?}", fn_name),
+                        repeat
+                    ),
+                    toggle_classes,
+                    class_name
+                ));
+            }
+        }
+    }
+    // the listeners for the same event are combined after the loop
+    let mut listeners: Vec<(&str, Vec<String>)> = vec![];
+    for attribute in &template_element.attributes {
+        match attribute {
+            TemplateAttribute::ReplaceString { fn_name, .. } => {
+                replace_string = Some(fn_name);
+            }
+            TemplateAttribute::ToggleClass { .. } => {
+                // already generated before the class attribute
+            }
+            TemplateAttribute::Listener {
+                event,
+                fn_name,
+                modifiers,
+                args,
+            } => {
+                let listener_code = listener_code(event, fn_name, modifiers, args, repeat);
+                match listeners.iter_mut().find(|(x, _)| x == event) {
+                    Some((_, event_listeners)) => event_listeners.push(listener_code),
+                    None => listeners.push((event, vec![listener_code])),
+                }
+            }
+            TemplateAttribute::RetainBoolean { fn_name, .. } => {
+                replace_boolean_attribute = Some(fn_name);
+            }
+            TemplateAttribute::Static { name, .. }
+            | TemplateAttribute::Interpolated { name, .. } => {
+                let value_code = match (replace_string.take(), attribute) {
+                    (Some(fn_name), _) => replaced_string_code(fn_name, repeat),
+                    (None, TemplateAttribute::Interpolated { parts, .. }) => {
+                        text_parts_code(parts, repeat)
+                    }
+                    (None, TemplateAttribute::Static { value, .. }) => format!("{:?}", value),
+                    (None, _) => "\"\"".to_owned(),
+                };
+                // the xmlns declarations are not rendered
+                let name = match html_or_svg_element.attribute_name(name) {
+                    Some(name) => name,
+                    None => {
+                        replace_boolean_attribute = None;
+                        continue;
+                    }
+                };
+                let attr_statement = if name == ATTR_KEY {
+                    // data-key is the key for the dodrio diff, not an attribute
+                    match parent_name {
+                        Some(parent_name) => format!(
+                            "{{ let key: &str = {}; sibling_keys_{}.insert(key, {:?}, {:?}, {})?; {} = {}.key(sibling_keys_{}.unique_node_key(key)); }}",
+                            value_code,
+                            depth - 1,
+                            parent_name,
+                            template_element.name,
+                            position_code(template_element.key_position.as_ref()),
+                            element,
+                            element,
+                            depth - 1
+                        ),
+                        None => format!(
+                            "{} = {}.key(::rust_wasm_dodrio_templating::html_template_mod::node_key({}));",
+                            element, element, value_code
+                        ),
+                    }
+                } else if has_toggle_classes && name == "class" {
+                    format!(
+                        "{} = true; {} = {}.attr(\"class\", {});",
+                        class_is_rendered,
+                        element,
+                        element,
+                        add_classes_code(&value_code, &toggle_classes)
+                    )
+                } else {
+                    format!(
+                        "{} = {}.attr({:?}, {});",
+                        element, element, name, value_code
+                    )
+                };
+                if let Some(fn_name) = replace_boolean_attribute.take() {
+                    unwrap!(writeln!(
+                        code,
+                        "{}if {} {{ {} }}",
+                        indent,
+                        method_call_code(
+                            "retain_next_node_or_attribute",
+                            &format!("{:?}", fn_name),
+                            repeat
+                        ),
+                        attr_statement
+                    ));
+                } else {
+                    unwrap!(writeln!(code, "{}{}", indent, attr_statement));
+                }
+            }
+        }
+    }
+    if has_toggle_classes {
+        // the element has no class attribute in the template
+        unwrap!(writeln!(
+            code,
+            "{}if !{} && !{}.is_empty() {{ {} = {}.attr(\"class\", {}); }}",
+            indent,
+            class_is_rendered,
+            toggle_classes,
+            element,
+            element,
+            add_classes_code("\"\"", &toggle_classes)
+        ));
+    }
+    // dodrio has only one listener per event on the element
+    for (event, event_listeners) in listeners {
+        let listener = if event_listeners.len() == 1 {
+            event_listeners.join("")
+        } else {
+            format!(
+                "::rust_wasm_dodrio_templating::event_listener_mod::combine_listeners(vec![{}])",
+                event_listeners.join(", ")
+            )
+        };
+        unwrap!(writeln!(
+            code,
+            "{}{} = {}.on({:?}, {});",
+            indent, element, element, event, listener
+        ));
+    }
+    for template_node in &template_element.children {
+        match template_node {
+            TemplateNode::Element(template_child) => {
+                let repeat_fn_name = replace_repeat.take();
+                let mut repeat_loop = String::new();
+                if let Some(fn_name) = repeat_fn_name {
+                    // the count is called with the outer items, the children with this item too
+                    let index = format!("repeat_index_{}", depth);
+                    unwrap!(write!(
+                        repeat_loop,
+                        "for {} in 0..rrc.repeat_count({:?}, {}) ",
+                        index,
+                        fn_name,
+                        repeat_slice_code(repeat)
+                    ));
+                    repeat.push(format!(
+                        "::rust_wasm_dodrio_templating::html_template_mod::RepeatItem {{ fn_name: {:?}, index: {} }}",
+                        fn_name, index
+                    ));
+                }
+                let mut child_statement = String::new();
+                if let Some(fn_name) = replace_vec_nodes.take() {
+                    let nodes_code = if repeat.is_empty() {
+                        format!("rrc.replace_with_nodes(cx, {:?})", fn_name)
+                    } else {
+                        format!(
+                            "rrc.replace_with_nodes_in_repeat(cx, {:?}, {})",
+                            fn_name,
+                            repeat_slice_code(repeat)
+                        )
+                    };
+                    unwrap!(write!(
+                        child_statement,
+                        "for node in {} {{ {} = {}.child(node); }}",
+                        nodes_code, element, element
+                    ));
+                } else {
+                    let html_or_svg_child = html_or_svg_children.for_element(&template_child.name);
+                    // foreignObject changes to html for children, not for this element
+                    let html_or_svg_grandchildren =
+                        html_or_svg_child.for_children(&template_child.name);
+                    let child_code = generate_element(
+                        template_child,
+                        html_or_svg_child,
+                        html_or_svg_grandchildren,
+                        depth + 1,
+                        Some(&template_element.name),
+                        repeat,
+                    );
+                    unwrap!(write!(
+                        child_statement,
+                        "{} = {}.child({});",
+                        element, element, child_code
+                    ));
+                }
+                if repeat_fn_name.is_some() {
+                    repeat.pop();
+                    child_statement = format!("{}{{ {} }}", repeat_loop, child_statement);
+                }
+                let condition_code = |fn_name: &str| {
+                    method_call_code(
+                        "retain_next_node_or_attribute",
+                        &format!("{:?}", fn_name),
+                        repeat,
+                    )
+                };
+                match replace_boolean.take() {
+                    Some(TemplateNode::RetainBoolean(fn_name)) if has_else => {
+                        unwrap!(writeln!(
+                            code,
+                            "{}{} = {}; if {} {{ {} }}",
+                            indent,
+                            else_chain,
+                            condition_code(fn_name),
+                            else_chain,
+                            child_statement
+                        ));
+                    }
+                    Some(TemplateNode::RetainBoolean(fn_name)) => {
+                        unwrap!(writeln!(
+                            code,
+                            "{}if {} {{ {} }}",
+                            indent,
+                            condition_code(fn_name),
+                            child_statement
+                        ));
+                    }
+                    Some(TemplateNode::ElseIf(fn_name)) => {
+                        // the condition is not called if a previous condition was true
+                        unwrap!(writeln!(
+                            code,
+                            "{}if !{} {{ {} = {}; if {} {{ {} }} }}",
+                            indent,
+                            else_chain,
+                            else_chain,
+                            condition_code(fn_name),
+                            else_chain,
+                            child_statement
+                        ));
+                    }
+                    Some(_) => {
+                        unwrap!(writeln!(
+                            code,
+                            "{}if !{} {{ {} }}",
+                            indent, else_chain, child_statement
+                        ));
+                    }
+                    None => {
+                        unwrap!(writeln!(code, "{}{}", indent, child_statement));
+                    }
+                }
+            }
+            TemplateNode::Text(_) | TemplateNode::InterpolatedText(_) => {
+                let text_code = match (replace_string.take(), template_node) {
+                    (Some(fn_name), _) => replaced_string_code(fn_name, repeat),
+                    (None, TemplateNode::InterpolatedText(parts)) => text_parts_code(parts, repeat),
+                    (None, TemplateNode::Text(txt)) => format!("{:?}", txt),
+                    (None, _) => "\"\"".to_owned(),
+                };
+                unwrap!(writeln!(
+                    code,
+                    "{}{} = {}.child(::rust_wasm_dodrio_templating::dodrio::builder::text({}));",
+                    indent, element, element, text_code
+                ));
+            }
+            TemplateNode::ReplaceString(fn_name) => replace_string = Some(fn_name),
+            TemplateNode::ReplaceNodes(fn_name) => replace_vec_nodes = Some(fn_name),
+            TemplateNode::RetainBoolean(_) | TemplateNode::ElseIf(_) | TemplateNode::Else => {
+                replace_boolean = Some(template_node)
+            }
+            TemplateNode::Repeat(fn_name) => replace_repeat = Some(fn_name),
+            // the includes are resolved before the code is generated
+            TemplateNode::Include(_) => {}
+        }
+    }
+    unwrap!(write!(
+        code,
+        "{}{}.finish()\n{}}}",
+        indent,
+        element,
+        "    ".repeat(depth)
+    ));
+    // return
+    code
+}
+
+/// The code for a replaced string is the same for text nodes and attributes.
+fn replaced_string_code(fn_name: &str, repeat: &[String]) -> String {
+    format!(
+        "::rust_wasm_dodrio_templating::dodrio::bumpalo::format!(in bump, \"{{}}\", ::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&{}, rrc.entity_decoding())).into_bump_str()",
+        method_call_code("replace_with_string", &format!("{:?}", fn_name), repeat)
+    )
+}
+
+/// The code that joins the static parts and the replaced placeholders {{wt_xxx}}.
+fn text_parts_code(parts: &[TextPart], repeat: &[String]) -> String {
+    let mut code =
+        String::from("{ let mut txt = ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::new_in(bump); ");
+    for part in parts {
+        match part {
+            TextPart::Static(static_text) => {
+                unwrap!(write!(code, "txt.push_str({:?}); ", static_text));
+            }
+            TextPart::ReplaceString(fn_name) => {
+                unwrap!(write!(
+                    code,
+                    "txt.push_str(&::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&{}, rrc.entity_decoding())); ",
+                    method_call_code("replace_with_string", &format!("{:?}", fn_name), repeat)
+                ));
+            }
+        }
+    }
+    code.push_str("txt.into_bump_str() }");
+    // return
+    code
+}
+
+/// The code that adds the toggled classes to the value of the class attribute.
+fn add_classes_code(value_code: &str, toggle_classes: &str) -> String {
+    format!(
+        "::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::from_str_in(&::rust_wasm_dodrio_templating::html_template_mod::add_classes({}, &{}), bump).into_bump_str()",
+        value_code, toggle_classes
+    )
+}
+
+/// Outside of repeat calls the method, inside repeat calls the method with `_in_repeat`.
+fn method_call_code(method: &str, fn_name_code: &str, repeat: &[String]) -> String {
+    if repeat.is_empty() {
+        format!("rrc.{}({})", method, fn_name_code)
+    } else {
+        format!(
+            "rrc.{}_in_repeat({}, {})",
+            method,
+            fn_name_code,
+            repeat_slice_code(repeat)
+        )
+    }
+}
+
+/// the code for one listener with the arguments and the modifiers
+fn listener_code(
+    event: &str,
+    fn_name: &str,
+    modifiers: &[ListenerModifier],
+    args: &Option<Vec<TemplateListenerArg>>,
+    repeat: &[String],
+) -> String {
+    let listener = match args {
+        Some(args) => {
+            let args_code: Vec<String> = args
+                .iter()
+                .map(|arg| match arg {
+                    TemplateListenerArg::Static(value) => format!(
+                        "::rust_wasm_dodrio_templating::event_listener_mod::ListenerArg::parse({:?})",
+                        value
+                    ),
+                    TemplateListenerArg::Interpolated { parts, quoted } => format!(
+                        "::rust_wasm_dodrio_templating::event_listener_mod::ListenerArg::parse_replaced({}, {})",
+                        text_parts_code(parts, repeat),
+                        quoted
+                    ),
+                })
+                .collect();
+            format!(
+                "rrc.set_event_listener_with_args({:?}.to_string(), vec![{}], {})",
+                fn_name,
+                args_code.join(", "),
+                repeat_slice_code(repeat)
+            )
+        }
+        None => method_call_code(
+            "set_event_listener",
+            &format!("{:?}.to_string()", fn_name),
+            repeat,
+        ),
+    };
+    if modifiers.is_empty() {
+        return listener;
+    }
+    let modifiers: Vec<String> = modifiers
+        .iter()
+        .map(|modifier| match modifier {
+            ListenerModifier::Key(key) => format!(
+                "::rust_wasm_dodrio_templating::parsed_template_mod::ListenerModifier::Key({:?}.to_string())",
+                key
+            ),
+            _ => format!(
+                "::rust_wasm_dodrio_templating::parsed_template_mod::ListenerModifier::{:?}",
+                modifier
+            ),
+        })
+        .collect();
+    // return
+    format!(
+        "::rust_wasm_dodrio_templating::event_listener_mod::with_modifiers({}, vec![{}], {:?}, {:?})",
+        listener,
+        modifiers.join(", "),
+        event,
+        fn_name
+    )
+}
+
+/// the code for the slice of RepeatItems
+fn repeat_slice_code(repeat: &[String]) -> String {
+    format!("&[{}]", repeat.join(", "))
+}
+
+/// the code of the TemplatePosition for the error in render time
+fn position_code(position: Option<&TemplatePosition>) -> String {
+    match position {
+        Some(position) => format!(
+            "Some(&::rust_wasm_dodrio_templating::template_error_mod::TemplatePosition {{ template_name: {:?}.to_string(), line: {}, column: {} }})",
+            position.template_name, position.line, position.column
+        ),
+        None => "None".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_fn_calls_the_markers() {
+        let code = unwrap!(generate_template_fn(
+            "render_card",
+            r#"<div class="{{wt_class}}"><!--wb_show--><b>shown</b><ul><!--wr_items--><li data-on-click="wl_pick">x</li></ul></div>"#,
+        ));
+        assert!(code.contains("pub fn render_card<'a, R: "));
+        assert!(code.contains(r#"rrc.replace_with_string("wt_class")"#));
+        assert!(code.contains(r#"rrc.retain_next_node_or_attribute("wb_show")"#));
+        assert!(code.contains(r#"rrc.repeat_count("wr_items""#));
+        assert!(code.contains(r#"set_event_listener_in_repeat("wl_pick".to_string()"#));
+        // only the absolute paths to the re-exported crates
+        let bare = code
+            .replace("::rust_wasm_dodrio_templating::dodrio::", "")
+            .replace("::rust_wasm_dodrio_templating::web_sys::", "");
+        assert!(!bare.contains("dodrio::") && !bare.contains("web_sys::"));
+    }
+
+    #[test]
+    fn duplicate_key_error_has_the_position() {
+        let code = unwrap!(generate_template_fn(
+            "render_list",
+            "<ul>\n<!--wr_items--><li data-key=\"{{wt_item}}\">x</li></ul>",
+        ));
+        assert!(code.contains(
+            r#"TemplatePosition { template_name: "render_list".to_string(), line: 2, column: 20 }"#
+        ));
+    }
+
+    #[test]
+    fn includes_are_resolved_from_the_slice() {
+        let code = unwrap!(generate_rust_code(&[
+            ("render_page", "<div><!--wi_render_footer--><br /></div>"),
+            ("render_footer", "<footer>the footer text</footer>"),
+        ]));
+        assert!(code.contains("pub fn render_page<"));
+        assert!(code.contains("pub fn render_footer<"));
+        assert_eq!(code.matches("the footer text").count(), 2);
+    }
+
+    #[test]
+    fn template_errors_come_before_compile_time() {
+        let err = unwrap!(generate_template_fn("render_bad", "<div><p></div>").err());
+        assert!(matches!(
+            err.kind,
+            TemplateErrorKind::MismatchedEndTag { .. }
+        ));
+        let err = unwrap!(generate_rust_code(&[(
+            "render_page",
+            "<div><!--wi_render_missing--><br /></div>"
+        )])
+        .err());
+        assert!(matches!(err.kind, TemplateErrorKind::UnknownInclude { .. }));
+    }
+}
@@ -0,0 +1,438 @@
+//! **event_listener_mod**
+//! Generic wrappers around the event listeners from set_event_listener().
+//! The modifiers from the attribute name like data-on-click.prevent run here
+//! before the listener of the project.
+//! Dodrio keeps only one listener per event on one element,
+//! so more listeners for the same event are combined into one.
+//! The arguments from data-on-click="wl_xxx(17, 'a')" are parsed here into ListenerArg.
+//! The .debounce-300ms and .throttle-100ms modifiers limit how often an expensive listener runs.
+//! The state of .once and the timers is stored with the DOM element, because the listener closure
+//! is created again on every render. It is dropped when the element leaves the document.
+
+// region: use
+use crate::parsed_template_mod::ListenerModifier;
+use dodrio::{RootRender, VdomWeak};
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
+use std::rc::Rc;
+use unwrap::unwrap;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+// endregion: use
+
+/// The event listener as returned from set_event_listener().
+pub type ListenerBox = Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static>;
+
+/// One argument from data-on-click="wl_select_card(17, {{wt_name}})".
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListenerArg {
+    /// integer like 17 or -3
+    Int(i64),
+    /// number with decimals like 2.5
+    Float(f64),
+    /// true or false
+    Bool(bool),
+    /// quoted text 'abc' or "abc" or any other text
+    Str(String),
+}
+
+impl ListenerArg {
+    /// parses one argument, without quotes it tries bool, integer and float
+    pub fn parse(arg: &str) -> ListenerArg {
+        let arg = arg.trim();
+        for quote in ['\'', '"'] {
+            if arg.len() >= 2 && arg.starts_with(quote) && arg.ends_with(quote) {
+                return ListenerArg::Str(arg[1..arg.len() - 1].to_owned());
+            }
+        }
+        if let Ok(value) = arg.parse::<bool>() {
+            ListenerArg::Bool(value)
+        } else if let Ok(value) = arg.parse::<i64>() {
+            ListenerArg::Int(value)
+        } else if let Ok(value) = arg.parse::<f64>() {
+            ListenerArg::Float(value)
+        } else {
+            ListenerArg::Str(arg.to_owned())
+        }
+    }
+
+    /// The argument after the placeholders were replaced.
+    /// The argument in quotes is text, without quotes it is parsed, but the quotes
+    /// inside the replaced text stay text.
+    pub fn parse_replaced(value: &str, quoted: bool) -> ListenerArg {
+        if quoted {
+            return ListenerArg::Str(value.to_owned());
+        }
+        match ListenerArg::parse(value) {
+            ListenerArg::Str(_) => ListenerArg::Str(value.trim().to_owned()),
+            listener_arg => listener_arg,
+        }
+    }
+
+    /// the integer, also from a float without decimals
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ListenerArg::Int(value) => Some(*value),
+            ListenerArg::Float(value) if value.fract() == 0.0 => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    /// the index for the Vec, only for not negative integers
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_i64().and_then(|value| usize::try_from(value).ok())
+    }
+
+    /// the number, also from an integer
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ListenerArg::Int(value) => Some(*value as f64),
+            ListenerArg::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// only for true and false
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ListenerArg::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// the text, only for the Str variant
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ListenerArg::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the arguments separated by commas.
+/// The commas inside quotes do not split. Empty parentheses are no arguments.
+pub fn parse_listener_args(args: &str) -> Vec<ListenerArg> {
+    split_listener_args(args)
+        .into_iter()
+        .map(ListenerArg::parse)
+        .collect()
+}
+
+/// Splits the arguments separated by commas, not parsed yet.
+/// The commas inside quotes do not split. Empty parentheses are no arguments.
+pub fn split_listener_args(args: &str) -> Vec<&str> {
+    if args.trim().is_empty() {
+        return vec![];
+    }
+    let mut listener_args = vec![];
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+    for (pos, ch) in args.char_indices() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => {}
+            None if ch == '\'' || ch == '"' => quote = Some(ch),
+            None if ch == ',' => {
+                listener_args.push(&args[start..pos]);
+                start = pos + 1;
+            }
+            None => {}
+        }
+    }
+    listener_args.push(&args[start..]);
+    // return
+    listener_args
+}
+
+/// The state of the modifiers that must survive the render.
+/// set_event_listener() creates a new closure on every render,
+/// so the state cannot live inside the closure.
+#[derive(Default)]
+pub struct ModifierState {
+    /// the .once listener was already called
+    is_done: Cell<bool>,
+    /// the time of the last call for .throttle
+    last_call: Cell<Option<f64>>,
+    /// the waiting timer for .debounce
+    timeout_handle: Cell<Option<i32>>,
+}
+
+/// What to do with the event after the modifiers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModifierDecision {
+    /// the filter or the timer stopped the event
+    Skip,
+    /// call the listener now
+    Call,
+    /// call the listener after the .debounce milliseconds
+    Debounce(u32),
+}
+
+/// The state of one listener on one DOM element.
+struct ElementState {
+    element: web_sys::Element,
+    /// event:fn_name, more listeners can be on the same element
+    listener_name: String,
+    state: Rc<ModifierState>,
+}
+
+thread_local! {
+    /// The states of the dodrio listeners with modifiers, stored with the DOM element.
+    /// Dodrio keeps the element between renders, also when a keyed item moves.
+    static ELEMENT_STATES: RefCell<Vec<ElementState>> = const { RefCell::new(vec![]) };
+}
+
+/// The key of the modifier state for the backends that create the elements again on every render.
+/// The element_path has the child indexes in the template and the data-key
+/// or the repeat index of the repeated elements, like html/1/3#17/0.
+pub fn listener_state_key(element_path: &str, event: &str, fn_name: &str) -> String {
+    format!("{}:{}:{}", element_path, event, fn_name)
+}
+
+/// The state for the listener on the element of the event, the same on every render.
+/// The states of the elements removed from the document are dropped,
+/// if their .debounce timer is not waiting.
+fn element_modifier_state(event: &web_sys::Event, listener_name: &str) -> Rc<ModifierState> {
+    let element = match event
+        .current_target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+    {
+        Some(element) => element,
+        None => return Rc::default(),
+    };
+    ELEMENT_STATES.with(|states| {
+        let mut states = states.borrow_mut();
+        states.retain(|x| x.element.is_connected() || x.state.timeout_handle.get().is_some());
+        if let Some(x) = states
+            .iter()
+            .find(|x| x.element == element && x.listener_name == listener_name)
+        {
+            return x.state.clone();
+        }
+        let state = Rc::new(ModifierState::default());
+        states.push(ElementState {
+            element,
+            listener_name: listener_name.to_owned(),
+            state: state.clone(),
+        });
+        // return
+        state
+    })
+}
+
+impl ModifierState {
+    /// Runs the modifiers before the listener.
+    /// The filters .self and keys are checked first. If the event does not pass,
+    /// nothing happens, also no prevent_default().
+    pub fn decide(
+        &self,
+        modifiers: &[ListenerModifier],
+        event: &web_sys::Event,
+    ) -> ModifierDecision {
+        for modifier in modifiers {
+            match modifier {
+                ListenerModifier::SelfOnly => {
+                    let target = event.target().map(JsValue::from);
+                    let current_target = event.current_target().map(JsValue::from);
+                    if target != current_target {
+                        return ModifierDecision::Skip;
+                    }
+                }
+                ListenerModifier::Key(key) => {
+                    let is_key =
+                        event
+                            .dyn_ref::<web_sys::KeyboardEvent>()
+                            .is_some_and(|keyboard_event| {
+                                keyboard_event.key().eq_ignore_ascii_case(key)
+                            });
+                    if !is_key {
+                        return ModifierDecision::Skip;
+                    }
+                }
+                ListenerModifier::Prevent
+                | ListenerModifier::Stop
+                | ListenerModifier::Once
+                | ListenerModifier::Debounce(_)
+                | ListenerModifier::Throttle(_) => {}
+            }
+        }
+        // the .once is done only when the listener runs, not when a timer stops it
+        if modifiers.contains(&ListenerModifier::Once) && self.is_done.get() {
+            return ModifierDecision::Skip;
+        }
+        if modifiers.contains(&ListenerModifier::Prevent) {
+            event.prevent_default();
+        }
+        if modifiers.contains(&ListenerModifier::Stop) {
+            event.stop_propagation();
+        }
+        // prevent_default() works only now, so the timers come after it
+        for modifier in modifiers {
+            match modifier {
+                ListenerModifier::Throttle(milliseconds) => {
+                    let now = js_sys::Date::now();
+                    if let Some(last_call) = self.last_call.get() {
+                        if now - last_call < f64::from(*milliseconds) {
+                            return ModifierDecision::Skip;
+                        }
+                    }
+                    self.last_call.set(Some(now));
+                }
+                ListenerModifier::Debounce(milliseconds) => {
+                    return ModifierDecision::Debounce(*milliseconds);
+                }
+                _ => {}
+            }
+        }
+        if !self.start_call(modifiers) {
+            return ModifierDecision::Skip;
+        }
+        // return
+        ModifierDecision::Call
+    }
+
+    /// Marks the .once listener as done just before it runs.
+    /// Returns false if it already ran.
+    pub fn start_call(&self, modifiers: &[ListenerModifier]) -> bool {
+        if !modifiers.contains(&ListenerModifier::Once) {
+            return true;
+        }
+        // return
+        !self.is_done.replace(true)
+    }
+}
+
+/// Clears the waiting timer and starts a new one.
+/// The timer handle is in the state, so the listener from the next render clears it too.
+/// When the timer ends, the .once is checked again, because an earlier timer could run the listener.
+pub fn restart_debounce_timer(
+    state: &Rc<ModifierState>,
+    modifiers: &[ListenerModifier],
+    milliseconds: u32,
+    callback: impl FnOnce() + 'static,
+) {
+    let window = unwrap!(web_sys::window());
+    if let Some(handle) = state.timeout_handle.take() {
+        window.clear_timeout_with_handle(handle);
+    }
+    let state_clone = state.clone();
+    let modifiers = modifiers.to_vec();
+    let callback = Closure::once_into_js(move || {
+        state_clone.timeout_handle.set(None);
+        if state_clone.start_call(&modifiers) {
+            callback();
+        }
+    });
+    let handle = unwrap!(
+        window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.unchecked_ref(),
+            i32::try_from(milliseconds).unwrap_or(i32::MAX),
+        )
+    );
+    state.timeout_handle.set(Some(handle));
+}
+
+/// Wraps the listener with the modifiers.
+/// The state of .once, .throttle and .debounce is stored with the DOM element of the event,
+/// because this closure is created again on every render.
+/// When the .debounce timer ends, the listener gets the root from the vdom.
+pub fn with_modifiers(
+    listener: ListenerBox,
+    modifiers: Vec<ListenerModifier>,
+    event_name: &str,
+    fn_name: &str,
+) -> ListenerBox {
+    if modifiers.is_empty() {
+        return listener;
+    }
+    let listener_name = format!("{}:{}", event_name, fn_name);
+    let listener: Rc<ListenerBox> = Rc::new(listener);
+    Box::new(move |root, vdom, event| {
+        let state = element_modifier_state(&event, &listener_name);
+        match state.decide(&modifiers, &event) {
+            ModifierDecision::Skip => {}
+            ModifierDecision::Call => listener(root, vdom, event),
+            ModifierDecision::Debounce(milliseconds) => {
+                let listener = listener.clone();
+                restart_debounce_timer(&state, &modifiers, milliseconds, move || {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let vdom_clone = vdom.clone();
+                        let _ = vdom
+                            .with_component(move |root| listener(root, vdom_clone, event))
+                            .await;
+                    });
+                });
+            }
+        }
+    })
+}
+
+/// Combines the listeners for the same event into one listener.
+/// They are called in the order of the template.
+pub fn combine_listeners(mut listeners: Vec<ListenerBox>) -> ListenerBox {
+    if listeners.len() == 1 {
+        return listeners.remove(0);
+    }
+    Box::new(move |root, vdom, event| {
+        for listener in &listeners {
+            listener(root, vdom.clone(), event.clone());
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_listener_args_kinds() {
+        assert_eq!(
+            parse_listener_args("17, -3, 2.5, true, 'a, b', \"c\", abc"),
+            vec![
+                ListenerArg::Int(17),
+                ListenerArg::Int(-3),
+                ListenerArg::Float(2.5),
+                ListenerArg::Bool(true),
+                ListenerArg::Str("a, b".to_owned()),
+                ListenerArg::Str("c".to_owned()),
+                ListenerArg::Str("abc".to_owned()),
+            ]
+        );
+        assert_eq!(parse_listener_args("  "), vec![]);
+    }
+
+    #[test]
+    fn parse_replaced_quoted_is_text() {
+        assert_eq!(
+            ListenerArg::parse_replaced("17", true),
+            ListenerArg::Str("17".to_owned())
+        );
+        assert_eq!(
+            ListenerArg::parse_replaced(" 17 ", false),
+            ListenerArg::Int(17)
+        );
+        // the quotes from the replaced text are not removed
+        assert_eq!(
+            ListenerArg::parse_replaced("'x'", false),
+            ListenerArg::Str("'x'".to_owned())
+        );
+    }
+
+    #[test]
+    fn listener_state_key_with_path() {
+        assert_eq!(
+            listener_state_key("html/1/0#17/2", "click", "wl_pick"),
+            "html/1/0#17/2:click:wl_pick"
+        );
+    }
+
+    #[test]
+    fn once_is_done_only_after_the_call() {
+        let state = ModifierState::default();
+        assert!(state.start_call(&[ListenerModifier::Throttle(100)]));
+        assert!(!state.is_done.get());
+        let once = [ListenerModifier::Once, ListenerModifier::Debounce(300)];
+        assert!(state.start_call(&once));
+        assert!(!state.start_call(&once));
+    }
+}
@@ -0,0 +1,181 @@
+//! **html_entity_mod**
+//! Decoding of html entities in the text and attribute values of the templates.
+//! The templates exported from design tools are full of `&nbsp;`, `&copy;`, `&#8364;` and `&#x2014;`.
+//! The complete list of html5 named entities comes from the crate `entities`.
+//! The old minimal decoding of only 5 xml control characters is still available.
+
+// region: use
+use crate::html_template_mod::decode_5_xml_control_characters;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+// endregion: use
+
+/// How the html entities in the template and in the replaced strings are decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum EntityDecoding {
+    /// only the 5 xml control characters: &quot; &apos; &amp; &lt; &gt;
+    Minimal,
+    /// all html5 named entities and the decimal and hex numeric references
+    #[default]
+    Html5,
+}
+
+/// the longest html5 named entity is &CounterClockwiseContourIntegral;
+const MAX_ENTITY_LEN: usize = 33;
+
+/// decodes the entities in the string as defined by the option
+pub fn decode_entities(input: &str, entity_decoding: EntityDecoding) -> String {
+    match entity_decoding {
+        EntityDecoding::Minimal => decode_5_xml_control_characters(input),
+        EntityDecoding::Html5 => decode_html_entities(input),
+    }
+}
+
+/// Decodes all html5 named entities like &nbsp; &copy;
+/// and numeric references like &#8364; &#x2014;.
+/// The entity must end with a semicolon.
+/// Unknown or malformed entities stay in the text as they are.
+pub fn decode_html_entities(input: &str) -> String {
+    if !input.contains('&') {
+        return input.to_owned();
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match decode_one_entity(rest) {
+            Some((decoded, len)) => {
+                output.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    // return
+    output
+}
+
+/// The input starts with &.
+/// Returns the decoded characters and the byte length of the entity.
+fn decode_one_entity(input: &str) -> Option<(String, usize)> {
+    let end = input
+        .char_indices()
+        .take(MAX_ENTITY_LEN)
+        .find(|(_, ch)| *ch == ';')
+        .map(|(pos, _)| pos)?;
+    let entity = &input[..=end];
+    let decoded = if let Some(number) = entity[1..end].strip_prefix('#') {
+        let code_point = if let Some(hex) = number
+            .strip_prefix('x')
+            .or_else(|| number.strip_prefix('X'))
+        {
+            parse_digits(hex, 16)?
+        } else {
+            parse_digits(number, 10)?
+        };
+        numeric_reference(code_point).to_string()
+    } else {
+        named_entities().get(entity)?.to_string()
+    };
+    // return
+    Some((decoded, end + 1))
+}
+
+/// only ascii digits, because from_str_radix accepts also a leading +
+fn parse_digits(digits: &str, radix: u32) -> Option<u32> {
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+        return None;
+    }
+    // too big numbers are not valid characters anyway
+    Some(u32::from_str_radix(digits, radix).unwrap_or(u32::MAX))
+}
+
+/// The numeric reference rules of the html5 spec:
+/// null, surrogates and numbers out of range become the replacement character,
+/// the range 0x80..0x9F is interpreted as windows-1252 like the browsers do.
+fn numeric_reference(code_point: u32) -> char {
+    let code_point = match code_point {
+        0x80 => 0x20AC,
+        0x82 => 0x201A,
+        0x83 => 0x0192,
+        0x84 => 0x201E,
+        0x85 => 0x2026,
+        0x86 => 0x2020,
+        0x87 => 0x2021,
+        0x88 => 0x02C6,
+        0x89 => 0x2030,
+        0x8A => 0x0160,
+        0x8B => 0x2039,
+        0x8C => 0x0152,
+        0x8E => 0x017D,
+        0x91 => 0x2018,
+        0x92 => 0x2019,
+        0x93 => 0x201C,
+        0x94 => 0x201D,
+        0x95 => 0x2022,
+        0x96 => 0x2013,
+        0x97 => 0x2014,
+        0x98 => 0x02DC,
+        0x99 => 0x2122,
+        0x9A => 0x0161,
+        0x9B => 0x203A,
+        0x9C => 0x0153,
+        0x9E => 0x017E,
+        0x9F => 0x0178,
+        0 => 0xFFFD,
+        other => other,
+    };
+    // char::from_u32 returns None for surrogates and out of range
+    std::char::from_u32(code_point).unwrap_or('\u{FFFD}')
+}
+
+/// The map of named entities is created once on the first use.
+/// Only the entities with the semicolon are used,
+/// the legacy entities without it are ambiguous in the middle of the text.
+fn named_entities() -> &'static HashMap<&'static str, &'static str> {
+    static NAMED_ENTITIES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    NAMED_ENTITIES.get_or_init(|| {
+        entities::ENTITIES
+            .iter()
+            .filter(|entity| entity.entity.ends_with(';'))
+            .map(|entity| (entity.entity, entity.characters))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_html_entities_named_and_numeric() {
+        assert_eq!(decode_html_entities("a&nbsp;&copy;b"), "a\u{a0}©b");
+        assert_eq!(decode_html_entities("&#8364; &#x2014; &#X41;"), "€ — A");
+        assert_eq!(decode_html_entities("&lt;p&gt; &amp;amp;"), "<p> &amp;");
+    }
+
+    #[test]
+    fn decode_html_entities_unknown_stays() {
+        assert_eq!(decode_html_entities("a & b"), "a & b");
+        assert_eq!(decode_html_entities("&nbsp"), "&nbsp");
+        assert_eq!(decode_html_entities("&unknown;"), "&unknown;");
+        assert_eq!(decode_html_entities("&#xZZ;"), "&#xZZ;");
+    }
+
+    #[test]
+    fn decode_entities_minimal() {
+        assert_eq!(
+            decode_entities("&lt;&quot;&nbsp;", EntityDecoding::Minimal),
+            "<\"&nbsp;"
+        );
+        assert_eq!(
+            decode_entities("&lt;&quot;&nbsp;", EntityDecoding::Html5),
+            "<\"\u{a0}"
+        );
+    }
+}
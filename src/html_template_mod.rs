@@ -4,23 +4,87 @@
 //! implement the trait HtmlTemplating
 
 // region: use
-use reader_for_microxml::*;
+use crate::event_listener_mod::*;
+use crate::html_entity_mod::*;
+use crate::parsed_template_mod::*;
+use crate::render_target_mod::*;
+use crate::server_render_mod::render_to_string;
+use crate::template_error_mod::*;
+use crate::template_include_mod::*;
+use crate::template_library_mod::*;
 
 use dodrio::{
-    builder::{text, ElementBuilder},
+    builder::ElementBuilder,
     bumpalo::{self},
-    Attribute, Listener, Node, RenderContext, RootRender, VdomWeak,
+    Node, RenderContext, RootRender, VdomWeak,
 };
-use unwrap::unwrap;
 // endregion: use
 
-/// Svg elements are different because they have a namespace
-#[derive(Clone, Copy)]
+/// Svg and MathML elements are different because they have a namespace.  
+/// The tag name svg or math changes the namespace for the element and its children.  
+/// A new namespace needs a variant, the uri and the tag names that switch to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HtmlOrSvg {
     /// html element
     Html,
     /// svg element
     Svg,
+    /// MathML element
+    MathMl,
+}
+
+impl HtmlOrSvg {
+    /// The namespace for dodrio. Html elements have no namespace.
+    pub fn namespace_uri(self) -> Option<&'static str> {
+        match self {
+            HtmlOrSvg::Html => None,
+            HtmlOrSvg::Svg => Some("http://www.w3.org/2000/svg"),
+            HtmlOrSvg::MathMl => Some("http://www.w3.org/1998/Math/MathML"),
+        }
+    }
+
+    /// The namespace of the element with this tag name inside the parent with self namespace.
+    pub fn for_element(self, tag_name: &str) -> HtmlOrSvg {
+        match tag_name {
+            "svg" => HtmlOrSvg::Svg,
+            "math" => HtmlOrSvg::MathMl,
+            _ => self,
+        }
+    }
+
+    /// The namespace of the children of the element with self namespace.
+    /// foreignObject changes to html for children, not for the element itself.
+    pub fn for_children(self, tag_name: &str) -> HtmlOrSvg {
+        match (self, tag_name) {
+            (HtmlOrSvg::Svg, "foreignObject") => HtmlOrSvg::Html,
+            _ => self,
+        }
+    }
+
+    /// Dodrio sets the attributes without namespace (no setAttributeNS).  
+    /// xlink:href becomes the SVG 2 href and xml:lang becomes lang.  
+    /// The xmlns and xmlns:xxx declarations are not rendered, because the namespace
+    /// comes from the element. Returns None for attributes that are not rendered.
+    pub fn attribute_name(self, name: &str) -> Option<&str> {
+        match name {
+            "xlink:href" => Some("href"),
+            "xml:lang" => Some("lang"),
+            _ if name == "xmlns" || name.starts_with("xmlns:") => None,
+            _ => Some(name),
+        }
+    }
+}
+
+/// One item of the repeat marker <!--wr_xxx-->.  
+/// Inside the repeated node the markers are replaced for this item.  
+/// The repeat markers can be nested, so the methods `_in_repeat` get the slice
+/// of all the items from the outermost to the innermost.
+#[derive(Clone, Copy, Debug)]
+pub struct RepeatItem<'t> {
+    /// the name of the repeat marker like wr_items
+    pub fn_name: &'t str,
+    /// the index of the item, starts with 0
+    pub index: usize,
 }
 
 /// the RootRenderingComponent struct must implement this trait
@@ -29,280 +93,198 @@ pub trait HtmlTemplating {
     // region: methods to be implemented for a specific project
     // while rendering, cannot mut rrc
     fn replace_with_string(&self, fn_name: &str) -> String;
+    #[allow(clippy::extra_unused_lifetimes)]
     fn retain_next_node_or_attribute<'a>(&self, fn_name: &str) -> bool;
     fn replace_with_nodes<'a>(&self, cx: &mut RenderContext<'a>, fn_name: &str) -> Vec<Node<'a>>;
+    #[allow(clippy::type_complexity)]
     fn set_event_listener(
         &self,
         fn_name: String,
     ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static>;
     // endregion: methods to be implemented
 
+    // region: methods for repeat, to be implemented only if the template uses <!--wr_xxx-->
+    /// How many times to render the node after <!--wr_xxx-->.  
+    /// The repeat is the slice of the outer items if the repeat is nested.  
+    fn repeat_count(&self, _fn_name: &str, _repeat: &[RepeatItem]) -> usize {
+        0
+    }
+    /// replace_with_string() for the markers inside the repeated node.
+    fn replace_with_string_in_repeat(&self, fn_name: &str, _repeat: &[RepeatItem]) -> String {
+        self.replace_with_string(fn_name)
+    }
+    /// retain_next_node_or_attribute() for the markers inside the repeated node.
+    fn retain_next_node_or_attribute_in_repeat(
+        &self,
+        fn_name: &str,
+        _repeat: &[RepeatItem],
+    ) -> bool {
+        self.retain_next_node_or_attribute(fn_name)
+    }
+    /// replace_with_nodes() for the markers inside the repeated node.
+    fn replace_with_nodes_in_repeat<'a>(
+        &self,
+        cx: &mut RenderContext<'a>,
+        fn_name: &str,
+        _repeat: &[RepeatItem],
+    ) -> Vec<Node<'a>> {
+        self.replace_with_nodes(cx, fn_name)
+    }
+    /// set_event_listener() for the markers inside the repeated node.
+    #[allow(clippy::type_complexity)]
+    fn set_event_listener_in_repeat(
+        &self,
+        fn_name: String,
+        _repeat: &[RepeatItem],
+    ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static> {
+        self.set_event_listener(fn_name)
+    }
+    // endregion: methods for repeat
+
+    // region: methods for the listener arguments, to be implemented only if the template uses wl_xxx(args)
+    /// The listener for data-on-click="wl_xxx(17, {{wt_name}})".  
+    /// The placeholders are already replaced and the arguments parsed.  
+    /// The repeat is empty outside of <!--wr_xxx-->.  
+    /// The default ignores the arguments and calls set_event_listener() or set_event_listener_in_repeat().
+    #[allow(clippy::type_complexity)]
+    fn set_event_listener_with_args(
+        &self,
+        fn_name: String,
+        _args: Vec<ListenerArg>,
+        repeat: &[RepeatItem],
+    ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static> {
+        if repeat.is_empty() {
+            self.set_event_listener(fn_name)
+        } else {
+            self.set_event_listener_in_repeat(fn_name, repeat)
+        }
+    }
+    // endregion: methods for the listener arguments
+
+    // region: methods for server side rendering, to be implemented only if the template uses <!--wn_xxx-->
+    /// The html for <!--wn_xxx--> in render_template_to_string().  
+    /// The counterpart of replace_with_nodes(). The html is not escaped.
+    fn replace_with_html(&self, _fn_name: &str) -> String {
+        String::new()
+    }
+    /// replace_with_html() for the markers inside the repeated node.
+    fn replace_with_html_in_repeat(&self, fn_name: &str, _repeat: &[RepeatItem]) -> String {
+        self.replace_with_html(fn_name)
+    }
+    // endregion: methods for server side rendering
+
+    // region: options with defaults
+    /// How the html entities are decoded in the template and in the replaced strings.  
+    /// Return EntityDecoding::Minimal to keep the old decoding of only 5 xml control characters.
+    fn entity_decoding(&self) -> EntityDecoding {
+        EntityDecoding::default()
+    }
+    /// The source of the templates for the include markers <!--wi_xxx-->
+    /// and for the layouts data-layout="xxx".  
+    /// render_template() resolves the layout and the includes with it.
+    fn template_source(&self) -> Option<&dyn TemplateSource> {
+        None
+    }
+    /// All the marker names implemented for this RootRenderingComponent.  
+    /// The attribute macro #[html_templating] generates it.
+    fn marker_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+    // endregion: options with defaults
+
     // region: generic code (in trait definition)
 
     /// get root element Node.   
     /// I wanted to use dodrio::Node, but it has only private methods.  
     /// I must use dodrio element_builder.  
+    /// The html template is parsed every time. For repeated rendering
+    /// parse it once with `ParsedTemplate::parse()` and use `render_parsed()`.  
     fn render_template<'a>(
         &self,
         cx: &mut RenderContext<'a>,
         html_template: &str,
         html_or_svg_parent: HtmlOrSvg,
-    ) -> Result<Node<'a>, String> {
-        let mut reader_for_microxml = ReaderForMicroXml::new(html_template);
-        let mut dom_path = Vec::new();
-        let mut root_element;
-        let mut html_or_svg_local = html_or_svg_parent;
-        let bump = cx.bump;
-        // the first element must be root and is special
-        #[allow(clippy::single_match_else, clippy::wildcard_enum_match_arm)]
-        match reader_for_microxml.next() {
-            None => {
-                // return error
-                return Err("Error: no root element".to_owned());
-            }
-            Some(result_token) => {
-                match result_token {
-                    Result::Err(e) => {
-                        // return error
-                        return Err(format!("Error: {}", e));
-                    }
-                    Result::Ok(token) => {
-                        match token {
-                            Token::StartElement(name) => {
-                                dom_path.push(name.to_owned());
-                                let name = bumpalo::format!(in bump, "{}",name).into_bump_str();
-                                root_element = ElementBuilder::new(bump, name);
-                                if name == "svg" {
-                                    html_or_svg_local = HtmlOrSvg::Svg;
-                                }
-                                if let HtmlOrSvg::Svg = html_or_svg_local {
-                                    // svg elements have this namespace
-                                    root_element =
-                                        root_element.namespace(Some("http://www.w3.org/2000/svg"));
-                                }
-                                // recursive function can return error
-                                match self.fill_element_builder(
-                                    &mut reader_for_microxml,
-                                    root_element,
-                                    cx,
-                                    html_or_svg_local,
-                                    &mut dom_path,
-                                ) {
-                                    // the methods are move, so I have to return the moved value
-                                    Ok(new_root_element) => root_element = new_root_element,
-                                    Err(err) => {
-                                        return Err(err);
-                                    }
-                                }
-                            }
-                            _ => {
-                                // return error
-                                return Err("Error: no root element".to_owned());
-                            }
-                        }
-                    }
-                }
-            }
+    ) -> Result<Node<'a>, TemplateError> {
+        let parsed_template = self.parse_template(html_template)?;
+        self.render_parsed(cx, &parsed_template, html_or_svg_parent)
+    }
+
+    /// Parses the html template and resolves the layout and the includes
+    /// from the template_source().
+    fn parse_template(&self, html_template: &str) -> Result<ParsedTemplate, TemplateError> {
+        let mut parsed_template =
+            ParsedTemplate::parse_with_decoding("", html_template, self.entity_decoding())?;
+        if let Some(template_source) = self.template_source() {
+            parsed_template.resolve_layout("", template_source, self.entity_decoding())?;
+            parsed_template.resolve_includes("", template_source, self.entity_decoding())?;
         }
         // return
-        Ok(root_element.finish())
+        Ok(parsed_template)
+    }
+
+    /// get root element Node from the template parsed in advance.  
+    /// Nothing is tokenized here. Only the tag and attribute names are copied into the bump.  
+    /// The layout and the includes must be resolved in advance with
+    /// `resolve_layout()` and `resolve_includes()`.  
+    fn render_parsed<'a>(
+        &self,
+        cx: &mut RenderContext<'a>,
+        parsed_template: &ParsedTemplate,
+        html_or_svg_parent: HtmlOrSvg,
+    ) -> Result<Node<'a>, TemplateError> {
+        render_to_target(
+            self,
+            parsed_template,
+            html_or_svg_parent,
+            DodrioRenderTarget::new(cx),
+        )
     }
-    /// Recursive function to fill the Element with attributes and sub-nodes(Element, Text, Comment).  
-    /// Moves & Returns ElementBuilder or error.  
-    /// I must `move` ElementBuilder because its methods are all `move`.  
-    /// It makes the code less readable. It is only good for chaining and type changing.  
-    #[allow(clippy::too_many_lines, clippy::type_complexity)]
-    fn fill_element_builder<'a>(
+
+    /// get root element Node of the sub_template <template name="xxx">
+    /// from the TemplateLibrary.  
+    /// Usually used in replace_with_nodes() for the <!--wn_xxx--> marker.  
+    fn render_sub_template<'a>(
         &self,
-        reader_for_microxml: &mut ReaderForMicroXml,
-        mut element: ElementBuilder<
-            'a,
-            bumpalo::collections::Vec<'a, Listener<'a>>,
-            bumpalo::collections::Vec<'a, Attribute<'a>>,
-            bumpalo::collections::Vec<'a, Node<'a>>,
-        >,
         cx: &mut RenderContext<'a>,
+        template_library: &TemplateLibrary,
+        sub_template_name: &str,
         html_or_svg_parent: HtmlOrSvg,
-        dom_path: &mut Vec<String>,
-    ) -> Result<
-        ElementBuilder<
-            'a,
-            bumpalo::collections::Vec<'a, Listener<'a>>,
-            bumpalo::collections::Vec<'a, Attribute<'a>>,
-            bumpalo::collections::Vec<'a, Node<'a>>,
-        >,
-        String,
-    > {
-        let mut replace_string: Option<String> = None;
-        let mut replace_vec_nodes: Option<Vec<Node>> = None;
-        let mut replace_boolean: Option<bool> = None;
-        let mut html_or_svg_local;
-        let bump = cx.bump;
-        // loop through all the siblings in this iteration
-        loop {
-            // the children inherits html_or_svg from the parent, but cannot change the parent
-            html_or_svg_local = html_or_svg_parent;
-            match reader_for_microxml.next() {
-                None => {}
-                Some(result_token) => {
-                    match result_token {
-                        Result::Err(e) => {
-                            // return error
-                            return Err(format!("Error: {}", e));
-                        }
-                        Result::Ok(token) => {
-                            match token {
-                                Token::StartElement(name) => {
-                                    dom_path.push(name.to_owned());
-                                    // construct a child element and fill it (recursive)
-                                    let name = bumpalo::format!(in bump, "{}",name).into_bump_str();
-                                    let mut child_element = ElementBuilder::new(bump, name);
-                                    if name == "svg" {
-                                        // this tagname changes to svg now
-                                        html_or_svg_local = HtmlOrSvg::Svg;
-                                    }
-                                    if let HtmlOrSvg::Svg = html_or_svg_local {
-                                        // this is the
-                                        // svg elements have this namespace
-                                        child_element = child_element
-                                            .namespace(Some("http://www.w3.org/2000/svg"));
-                                    }
-                                    if name == "foreignObject" {
-                                        // this tagname changes to html for children, not for this element
-                                        html_or_svg_local = HtmlOrSvg::Html;
-                                    }
-                                    child_element = self.fill_element_builder(
-                                        reader_for_microxml,
-                                        child_element,
-                                        cx,
-                                        html_or_svg_local,
-                                        dom_path,
-                                    )?;
-                                    // if the boolean is empty or true then render the next node
-                                    if replace_boolean.unwrap_or(true) {
-                                        if let Some(repl_vec_nodes) = replace_vec_nodes {
-                                            for repl_node in repl_vec_nodes {
-                                                element = element.child(repl_node);
-                                            }
-                                            replace_vec_nodes = None;
-                                        } else {
-                                            element = element.child(child_element.finish());
-                                        }
-                                    }
-                                    if replace_boolean.is_some() {
-                                        replace_boolean = None;
-                                    }
-                                }
-                                Token::Attribute(name, value) => {
-                                    if name.starts_with("data-wt-") {
-                                        // the rest of the name does not matter,
-                                        // but it should be nice to be te name of the next attribute.
-                                        // The replace_string will always be applied to the next attribute.
-                                        let fn_name = value;
-                                        if &fn_name[..3] != "wt_" {
-                                            return Err(format!(
-                                                "{} value does not start with wt_ : {}.",
-                                                name, fn_name
-                                            ));
-                                        }
-                                        let repl_txt = self.replace_with_string(fn_name);
-                                        replace_string = Some(repl_txt);
-                                    } else if name.starts_with("data-on-") {
-                                        // it must look like data-on-click="wl_xxx" wl_ = webbrowser listener
-                                        // Only one listener for now because the api does not give me other method.
-                                        let fn_name = value.to_string();
-                                        let event_to_listen = unwrap!(name.get(8..)).to_string();
-                                        // rust_wasm_websys_utils::websysmod::debug_write(&format!("name.starts_with data-on- : .{}.{}.",&fn_name,&event_to_listen));
-                                        if !fn_name.is_empty() && &fn_name[..3] != "wl_" {
-                                            return Err(format!(
-                                                "{} value does not start with wl_ : {}.",
-                                                name, fn_name
-                                            ));
-                                        }
+    ) -> Result<Node<'a>, TemplateError> {
+        let sub_template = template_library.get_or_error(sub_template_name)?;
+        self.render_parsed(cx, sub_template, html_or_svg_parent)
+    }
 
-                                        let event_to_listen =
-                                            bumpalo::format!(in bump, "{}",&event_to_listen)
-                                                .into_bump_str();
-                                        element = element
-                                            .on(event_to_listen, self.set_event_listener(fn_name));
-                                    } else {
-                                        let name =
-                                            bumpalo::format!(in bump, "{}",name).into_bump_str();
-                                        let value2;
-                                        if let Some(repl) = replace_string {
-                                            value2 =
-                                            bumpalo::format!(in bump, "{}",decode_5_xml_control_characters(&repl))
-                                                .into_bump_str();
-                                            // empty the replace_string for the next node
-                                            replace_string = None;
-                                        } else {
-                                            value2 =
-                                            bumpalo::format!(in bump, "{}",decode_5_xml_control_characters(value))
-                                                .into_bump_str();
-                                        }
-                                        element = element.attr(name, value2);
-                                    }
-                                }
-                                Token::TextNode(txt) => {
-                                    let txt2;
-                                    if let Some(repl) = replace_string {
-                                        txt2 =
-                                            bumpalo::format!(in bump, "{}",decode_5_xml_control_characters(&repl))
-                                                .into_bump_str();
-                                        // empty the replace_string for the next node
-                                        replace_string = None;
-                                    } else {
-                                        txt2 = bumpalo::format!(in bump, "{}",decode_5_xml_control_characters(txt))
-                                            .into_bump_str();
-                                    }
-                                    // here accepts only utf-8.
-                                    // rust_wasm_websys_utils::websysmod::debug_write("text node");
-                                    // rust_wasm_websys_utils::websysmod::debug_write(txt2);
-                                    // only minimum html entities are decoded
-                                    element = element.child(text(txt2));
-                                }
-                                Token::Comment(txt) => {
-                                    // the main goal of comments is to change the value of the next text node
-                                    // with the result of a function
-                                    if txt == "end_of_wt" {
-                                        // a special comment <!--end_of_wt--> just to end the wt_ replace string
-                                        // if there are more replacing inside one text node
-                                        // rust_wasm_websys_utils::websysmod::debug_write("found comment <!--end_of_wt-->");
-                                    } else if txt.starts_with("wt_") {
-                                        // it must look like <!--wt_get_text-->  wt_ = webbrowser text
-                                        let repl_txt = self.replace_with_string(txt);
-                                        replace_string = Some(repl_txt);
-                                    } else if txt.starts_with("wn_") {
-                                        // it must look like <!--wn_get_nodes-->  wn_ = webbrowser nodes
-                                        let repl_vec_nodes = self.replace_with_nodes(cx, txt);
-                                        replace_vec_nodes = Some(repl_vec_nodes);
-                                    } else if txt.starts_with("wb_") {
-                                        // it must look like <!--wb_get_bool-->  wb_ = webbrowser boolean
-                                        // boolean if this is true than render the next node, else don't render
-                                        replace_boolean =
-                                            Some(self.retain_next_node_or_attribute(txt));
-                                    } else {
-                                        // nothing. it is really a comment
-                                    }
-                                }
-                                Token::EndElement(name) => {
-                                    let last_name = unwrap!(dom_path.pop());
-                                    // it can be also auto-closing element
-                                    if last_name == name || name == "" {
-                                        return Ok(element);
-                                    } else {
-                                        return Err(format!(
-                                            "End element not correct: starts <{}> ends </{}>",
-                                            last_name, name
-                                        ));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    /// Renders the html template to the html string, without RenderContext.  
+    /// Used natively on the server for the first paint or for the crawlers.  
+    /// The listeners are dropped and <!--wn_xxx--> calls replace_with_html().  
+    fn render_template_to_string(
+        &self,
+        html_template: &str,
+        html_or_svg_parent: HtmlOrSvg,
+    ) -> Result<String, TemplateError> {
+        let parsed_template = self.parse_template(html_template)?;
+        self.render_parsed_to_string(&parsed_template, html_or_svg_parent)
+    }
+
+    /// Renders the template parsed in advance to the html string.
+    fn render_parsed_to_string(
+        &self,
+        parsed_template: &ParsedTemplate,
+        html_or_svg_parent: HtmlOrSvg,
+    ) -> Result<String, TemplateError> {
+        render_to_string(self, parsed_template, html_or_svg_parent)
+    }
+
+    /// Renders the sub_template to the html string.  
+    /// Usually used in replace_with_html() for the <!--wn_xxx--> marker.
+    fn render_sub_template_to_string(
+        &self,
+        template_library: &TemplateLibrary,
+        sub_template_name: &str,
+        html_or_svg_parent: HtmlOrSvg,
+    ) -> Result<String, TemplateError> {
+        let sub_template = template_library.get_or_error(sub_template_name)?;
+        self.render_parsed_to_string(sub_template, html_or_svg_parent)
     }
     // endregion: generic code
 }
@@ -313,10 +295,104 @@ pub fn empty_div<'a>(cx: &mut RenderContext<'a>) -> Node<'a> {
     ElementBuilder::new(bump, "div").finish()
 }
 
+/// copy the string into the bump arena, so it lives as long as the dodrio Node
+pub(crate) fn bump_str<'a>(bump: &'a bumpalo::Bump, input: &str) -> &'a str {
+    bumpalo::collections::String::from_str_in(input, bump).into_bump_str()
+}
+
+/// The attribute data-key="{{wt_xxx}}" sets the key of the element for the dodrio diff.
+pub const ATTR_KEY: &str = "data-key";
+
+/// The dodrio key is u32. A number is used as it is, other text is hashed.  
+/// u32::MAX is reserved by dodrio for "no key".
+pub fn node_key(key: &str) -> u32 {
+    let key = key.trim();
+    if let Ok(number) = key.parse::<u32>() {
+        // "01" is not the same key as "1", so only the plain number is used as it is
+        if number != u32::MAX && number.to_string() == key {
+            return number;
+        }
+    }
+    // FNV-1a hash
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in key.bytes() {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    // return
+    hash.min(u32::MAX - 1)
+}
+
+/// The keys of the rendered sibling elements.  
+/// The key text must be unique among the siblings. Different texts can get the same dodrio key
+/// if the hashes collide, then the next free dodrio key is used.
+#[derive(Default)]
+pub struct SiblingKeys {
+    keys: Vec<String>,
+    node_keys: Vec<u32>,
+}
+
+impl SiblingKeys {
+    /// Remembers the key of the child element. The same key twice is an error
+    /// with the position of the data-key of the second element.
+    pub fn insert(
+        &mut self,
+        key: &str,
+        parent_name: &str,
+        child_name: &str,
+        key_position: Option<&TemplatePosition>,
+    ) -> Result<(), TemplateError> {
+        if self.keys.iter().any(|x| x == key) {
+            let key_position = key_position.cloned().unwrap_or_default();
+            return Err(TemplateError {
+                kind: TemplateErrorKind::DuplicateKey(key.to_owned()),
+                template_name: key_position.template_name,
+                line: key_position.line,
+                column: key_position.column,
+                dom_path: vec![parent_name.to_owned(), child_name.to_owned()],
+            });
+        }
+        self.keys.push(key.to_owned());
+        // return
+        Ok(())
+    }
+
+    /// The dodrio key for the key text, unique among the siblings.
+    pub fn unique_node_key(&mut self, key: &str) -> u32 {
+        let mut unique_key = node_key(key);
+        while self.node_keys.contains(&unique_key) {
+            // u32::MAX is reserved by dodrio
+            unique_key = if unique_key >= u32::MAX - 1 {
+                0
+            } else {
+                unique_key + 1
+            };
+        }
+        self.node_keys.push(unique_key);
+        // return
+        unique_key
+    }
+}
+
+/// adds the classes to the value of the class attribute
+pub fn add_classes(value: &str, classes: &[&str]) -> String {
+    let mut new_value = value.trim_end().to_owned();
+    for class_name in classes {
+        if !new_value.split_whitespace().any(|x| x == *class_name) {
+            if !new_value.is_empty() {
+                new_value.push(' ');
+            }
+            new_value.push_str(class_name);
+        }
+    }
+    // return
+    new_value
+}
+
 /// decode 5 xml control characters : " ' & < >  
 /// https://www.liquid-technologies.com/XML/EscapingData.aspx
-/// I will ignore all html entities, to keep things simple,
-/// because all others characters can be written as utf-8 characters.
+/// This is the minimal decoding. All other html entities are ignored.
+/// For the complete html5 entities use `html_entity_mod::decode_html_entities()`.
 /// https://www.tutorialspoint.com/html5/html5_entities.htm  
 pub fn decode_5_xml_control_characters(input: &str) -> String {
     // The standard library replace() function makes allocation,
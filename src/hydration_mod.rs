@@ -0,0 +1,365 @@
+//! **hydration_mod**
+//! The server renders the first paint with render_template_for_hydration().
+//! In the browser hydrate() checks that the existing DOM matches the template output
+//! and adopts it: the walker goes through the existing elements and only attaches the data-on- listeners.
+//! The data-wm markers and the <!--wn_xxx--> comments tell what the server rendered.
+//! Dodrio 0.2 cannot adopt the existing DOM nodes, so the hydrated page is a WebDomWidget.
+//! The mismatches are reported, usually the state on the server was different.
+//! Then the widget renders the template fresh instead of adopting the wrong DOM.
+
+// region: use
+use crate::html_template_mod::*;
+use crate::parsed_template_mod::*;
+use crate::render_target_mod::*;
+use crate::server_render_mod::*;
+use crate::template_error_mod::*;
+use crate::web_dom_mod::*;
+use unwrap::unwrap;
+use wasm_bindgen::JsCast;
+// endregion: use
+
+/// One difference between the server-rendered DOM and the template output in the browser.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HydrationMismatch {
+    /// names of the elements from the root element
+    pub dom_path: Vec<String>,
+    /// what the template renders in the browser
+    pub expected: String,
+    /// what is in the DOM from the server
+    pub found: String,
+}
+
+impl std::fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.dom_path.is_empty() {
+            write!(f, "<{}>: ", self.dom_path.join("> <"))?;
+        }
+        write!(f, "expected {}, found {}", self.expected, self.found)
+    }
+}
+
+/// Renders the template on the server with the hydration markers:
+/// the attribute data-wm="wb_xxx" on the elements retained by wb_ markers
+/// and the comments <!--wn_xxx--> and <!--/wn_xxx--> around the html from replace_with_html().
+pub fn render_template_for_hydration<T: HtmlTemplating + ?Sized>(
+    rrc: &T,
+    html_template: &str,
+) -> Result<String, TemplateError> {
+    let parsed_template = rrc.parse_template(html_template)?;
+    let mut html = String::new();
+    render_to_html_node(rrc, &parsed_template, HtmlOrSvg::Html, true)?.write_html(&mut html, true);
+    // return
+    Ok(html)
+}
+
+/// Compares the DOM in the container element with the template output.
+/// The html from <!--wn_xxx--> is not compared, only the comments around it.
+pub fn check_hydration<T: HtmlTemplating + ?Sized>(
+    rrc: &T,
+    html_template: &str,
+    container: &web_sys::Element,
+) -> Result<Vec<HydrationMismatch>, TemplateError> {
+    let parsed_template = rrc.parse_template(html_template)?;
+    check_parsed_hydration(rrc, &parsed_template, container)
+}
+
+/// check_hydration() for the already parsed template
+fn check_parsed_hydration<T: HtmlTemplating + ?Sized>(
+    rrc: &T,
+    parsed_template: &ParsedTemplate,
+    container: &web_sys::Element,
+) -> Result<Vec<HydrationMismatch>, TemplateError> {
+    let expected = render_to_html_node(rrc, parsed_template, HtmlOrSvg::Html, false)?;
+    let mut mismatches = vec![];
+    match container.first_element_child() {
+        Some(root) => compare_element(&expected, &root, &mut vec![], &mut mismatches),
+        None => mismatches.push(HydrationMismatch {
+            dom_path: vec![],
+            expected: describe_html_node(&expected),
+            found: "nothing".to_owned(),
+        }),
+    }
+    // return
+    Ok(mismatches)
+}
+
+/// Adopts the server-rendered DOM in the container and attaches the data-on- listeners.
+/// The nodes from the server stay in the page until the rrc asks to render again.
+/// If the DOM does not match, the mismatches are returned and the widget renders fresh.
+pub fn hydrate<R: HtmlTemplating + WebDomListener + 'static>(
+    container: &web_sys::Element,
+    rrc: R,
+    html_template: &str,
+) -> Result<(WebDomWidget<R>, Vec<HydrationMismatch>), TemplateError> {
+    let parsed_template = rrc.parse_template(html_template)?;
+    let widget = WebDomWidget::without_render(container, rrc, parsed_template);
+    let rrc = widget.rrc();
+    let mismatches = check_parsed_hydration(&*rrc.borrow(), widget.parsed_template(), container)?;
+    if mismatches.is_empty() {
+        let target = AdoptTarget {
+            container: container.clone(),
+            dispatch: widget.dispatch(),
+            elements: vec![],
+            listeners: vec![],
+            modifier_states: ModifierStates::default(),
+        };
+        let (listeners, modifier_states) = render_to_target(
+            &*rrc.borrow(),
+            widget.parsed_template(),
+            HtmlOrSvg::Html,
+            target,
+        )?;
+        widget.set_adopted(listeners, modifier_states);
+    } else {
+        widget.render()?;
+    }
+    // return
+    Ok((widget, mismatches))
+}
+
+/// The backend that walks the existing DOM instead of creating it.
+/// The DOM is already checked, so the elements come in the same order as from the walker.
+struct AdoptTarget {
+    container: web_sys::Element,
+    dispatch: WebDomDispatch,
+    // the opened elements with the next child node to adopt
+    elements: Vec<(web_sys::Element, Option<web_sys::Node>)>,
+    listeners: Vec<DomListener>,
+    modifier_states: ModifierStates,
+}
+
+impl AdoptTarget {
+    /// the next child node of the last opened element, or of the container for the root
+    fn next_node(&mut self) -> Option<web_sys::Node> {
+        match self.elements.last_mut() {
+            Some((_, next_node)) => next_node.take(),
+            None => self.container.first_child(),
+        }
+    }
+
+    fn set_next_node(&mut self, node: Option<web_sys::Node>) {
+        if let Some((_, next_node)) = self.elements.last_mut() {
+            *next_node = node;
+        }
+    }
+}
+
+impl RenderTarget for AdoptTarget {
+    type Node = (Vec<DomListener>, ModifierStates);
+
+    fn begin_element(&mut self, _name: &str, _html_or_svg: HtmlOrSvg) {
+        // the text nodes are already there
+        let mut node = self.next_node();
+        let element = loop {
+            let dom_node = unwrap!(node);
+            match dom_node.dyn_ref::<web_sys::Element>() {
+                Some(element) => break element.clone(),
+                None => node = dom_node.next_sibling(),
+            }
+        };
+        self.set_next_node(element.next_sibling());
+        let first_child = element.first_child();
+        self.elements.push((element, first_child));
+    }
+
+    fn attribute(&mut self, _name: &str, _value: &str) {
+        // the attributes are already there
+    }
+
+    fn listener<R: HtmlTemplating + ?Sized>(&mut self, event: &str, markers: &[ListenerMarker<R>]) {
+        let element = unwrap!(self.elements.last()).0.clone();
+        let listener = add_dom_listener(
+            &element,
+            event,
+            markers,
+            self.dispatch.clone(),
+            &mut self.modifier_states,
+        );
+        self.listeners.push(listener);
+    }
+
+    fn text(&mut self, _txt: &str) {
+        // the text is already there
+    }
+
+    fn splice_children<R: HtmlTemplating + ?Sized>(&mut self, marker: &SpliceMarker<R>) {
+        // the html from the server stays, the nodes until <!--/wn_xxx--> are skipped
+        let end_comment = format!("/{}", marker.fn_name);
+        let mut node = self.next_node();
+        while let Some(dom_node) = node {
+            node = dom_node.next_sibling();
+            if is_comment(&dom_node, &end_comment) {
+                break;
+            }
+        }
+        self.set_next_node(node);
+    }
+
+    fn end_element(&mut self) {
+        self.elements.pop();
+    }
+
+    fn finish(self) -> Option<(Vec<DomListener>, ModifierStates)> {
+        Some((self.listeners, self.modifier_states))
+    }
+}
+
+/// Recursive function that compares the element, attributes and children.
+fn compare_element(
+    expected: &HtmlNode,
+    element: &web_sys::Element,
+    dom_path: &mut Vec<String>,
+    mismatches: &mut Vec<HydrationMismatch>,
+) {
+    let (name, attributes, children) = match expected {
+        HtmlNode::Element {
+            name,
+            attributes,
+            children,
+            ..
+        } => (name, attributes, children),
+        _ => return,
+    };
+    let mut push_mismatch = |dom_path: &[String], expected: String, found: String| {
+        mismatches.push(HydrationMismatch {
+            dom_path: dom_path.to_vec(),
+            expected,
+            found,
+        })
+    };
+    if element.local_name() != *name {
+        push_mismatch(
+            dom_path,
+            describe_html_node(expected),
+            describe_dom_node(element),
+        );
+        return;
+    }
+    dom_path.push(name.to_owned());
+    for (attr_name, value) in attributes {
+        let found = element.get_attribute(attr_name);
+        if found.as_deref() != Some(value.as_str()) {
+            push_mismatch(
+                dom_path,
+                format!("{}=\"{}\"", attr_name, value),
+                found.map_or_else(
+                    || "no attribute".to_owned(),
+                    |found| format!("{}=\"{}\"", attr_name, found),
+                ),
+            );
+        }
+    }
+    for attr_name in element.get_attribute_names().iter() {
+        let attr_name = attr_name.as_string().unwrap_or_default();
+        if attr_name != ATTR_HYDRATION_MARKER && !attributes.iter().any(|(x, _)| *x == attr_name) {
+            push_mismatch(dom_path, "no attribute".to_owned(), attr_name);
+        }
+    }
+    compare_children(children, element, dom_path, mismatches);
+    dom_path.pop();
+}
+
+/// The browser merges the adjacent text nodes and drops the empty ones.
+/// After the first mismatch the rest of the children is not compared.
+fn compare_children(
+    children: &[HtmlNode],
+    element: &web_sys::Element,
+    dom_path: &mut Vec<String>,
+    mismatches: &mut Vec<HydrationMismatch>,
+) {
+    let mut expected_nodes: Vec<HtmlNode> = vec![];
+    for child in children {
+        match (child, expected_nodes.last_mut()) {
+            (HtmlNode::Text(txt), Some(HtmlNode::Text(last_txt))) => last_txt.push_str(txt),
+            _ => expected_nodes.push(child.clone()),
+        }
+    }
+    expected_nodes.retain(|node| !matches!(node, HtmlNode::Text(txt) if txt.trim().is_empty()));
+    let mut dom_nodes: Vec<web_sys::Node> = vec![];
+    let child_nodes = element.child_nodes();
+    for index in 0..child_nodes.length() {
+        if let Some(node) = child_nodes.item(index) {
+            let is_empty_text = node.node_type() == web_sys::Node::TEXT_NODE
+                && node.node_value().unwrap_or_default().trim().is_empty();
+            if !is_empty_text {
+                dom_nodes.push(node);
+            }
+        }
+    }
+
+    let mut dom_iter = dom_nodes.iter();
+    for expected in &expected_nodes {
+        let dom_node = match dom_iter.next() {
+            Some(dom_node) => dom_node,
+            None => {
+                mismatches.push(HydrationMismatch {
+                    dom_path: dom_path.clone(),
+                    expected: describe_html_node(expected),
+                    found: "nothing".to_owned(),
+                });
+                return;
+            }
+        };
+        let is_same = match expected {
+            HtmlNode::Element { .. } => match dom_node.dyn_ref::<web_sys::Element>() {
+                Some(element) => {
+                    compare_element(expected, element, dom_path, mismatches);
+                    true
+                }
+                None => false,
+            },
+            HtmlNode::Text(txt) => {
+                dom_node.node_type() == web_sys::Node::TEXT_NODE
+                    && dom_node.node_value().as_deref() == Some(txt.as_str())
+            }
+            HtmlNode::Html { fn_name, .. } => {
+                // skip the nodes until the end comment
+                let end_comment = format!("/{}", fn_name);
+                is_comment(dom_node, fn_name)
+                    && dom_iter.any(|dom_node| is_comment(dom_node, &end_comment))
+            }
+        };
+        if !is_same {
+            mismatches.push(HydrationMismatch {
+                dom_path: dom_path.clone(),
+                expected: describe_html_node(expected),
+                found: describe_dom_node(dom_node),
+            });
+            return;
+        }
+    }
+    if let Some(dom_node) = dom_iter.next() {
+        mismatches.push(HydrationMismatch {
+            dom_path: dom_path.clone(),
+            expected: "nothing".to_owned(),
+            found: describe_dom_node(dom_node),
+        });
+    }
+}
+
+fn is_comment(dom_node: &web_sys::Node, txt: &str) -> bool {
+    dom_node.node_type() == web_sys::Node::COMMENT_NODE
+        && dom_node.node_value().as_deref() == Some(txt)
+}
+
+fn describe_html_node(html_node: &HtmlNode) -> String {
+    match html_node {
+        HtmlNode::Element { name, .. } => format!("<{}>", name),
+        HtmlNode::Text(txt) => format!("text \"{}\"", txt),
+        HtmlNode::Html { fn_name, .. } => format!("<!--{}-->", fn_name),
+    }
+}
+
+/// the data-wm attribute tells the wb_ marker that retained the element on the server
+fn describe_dom_node(dom_node: &web_sys::Node) -> String {
+    if let Some(element) = dom_node.dyn_ref::<web_sys::Element>() {
+        match element.get_attribute(ATTR_HYDRATION_MARKER) {
+            Some(marker) => format!("<{}> retained by {}", element.local_name(), marker),
+            None => format!("<{}>", element.local_name()),
+        }
+    } else if dom_node.node_type() == web_sys::Node::COMMENT_NODE {
+        format!("<!--{}-->", dom_node.node_value().unwrap_or_default())
+    } else {
+        format!("text \"{}\"", dom_node.node_value().unwrap_or_default())
+    }
+}
@@ -29,7 +29,7 @@
 //! In my code I parse the html template as a microXml file. Basically they are the same with small effort. When I find a comment or "data-" attribute then the value of the next node is replaced.  
 //! I can replace attributes, strings and entire nodes. And I can insert event for behavior with "data-wt".  
 //! When developing, the html template is loaded and parsed and a dodrio node is created. That is not very fast. But I can change the html in real time and see it rendered without compiling the Rust code. This is super efficient for development.  
-//! I have in plans to add a Rust code generator, that creates the Rust code for the dodrio node before compile time. In that case nothing is parsed in runtime and I expect great speeds. But the flexibility of easily changing the html template is gone. For every change I must recompile the Rust code.  
+//! For the release build there is a Rust code generator, that creates the Rust code for the dodrio node before compile time. In that case nothing is parsed in runtime and I expect great speeds. But the flexibility of easily changing the html template is gone. For every change I must recompile the Rust code.  
 //!
 //! ## Used in projects
 //!
@@ -48,6 +48,19 @@
 //! <p><!--wt_new_text>old_text</p>
 //! ```
 //!
+//! ### Inline placeholders
+//!
+//! Inside a text node or an attribute value write the placeholder `{{wt_xxx}}`.  
+//! It is replaced with the result of the fn replace_with_string(). The rest of the text stays as it is.  
+//! One text node can have many placeholders, so there is no need for many comments and fragmented text.  
+//! The designer still sees the static html with the placeholders in the browser.  
+//! Curly braces without "wt_" are just text.  
+//!
+//! ```html
+//! <p>Hello {{wt_nick_name}}, you have {{wt_points}} points.</p>
+//! <div class="card {{wt_card_state}}"></div>
+//! ```
+//!
 //! ### Replace the next node with nodes  
 //!
 //! Insert a comment that starts with "wn_" (webbrowser nodes).  
@@ -68,6 +81,29 @@
 //! <input data-wt-value="wt_new_text" value="old text" />
 //! ```
 //!
+//! ### Retain or remove the next attribute  
+//!
+//! Insert an attribute that starts with "data-wb-" (webbrowser boolean).  
+//! The attribute name finishes in the name of the next attribute.  
+//! The attribute value is the enum to use in the fn retain_next_node_or_attribute().  
+//! If it returns false, the next attribute is not rendered. This is useful for boolean html attributes like disabled, checked, hidden and selected.  
+//!
+//! ```html
+//! <button data-wb-disabled="wb_is_locked" disabled="disabled">Play</button>
+//! ```
+//!
+//! ### Toggle a class  
+//!
+//! Insert an attribute that starts with "data-wc-" (webbrowser class).  
+//! The attribute name finishes in the name of the class.  
+//! The attribute value is the enum to use in the fn retain_next_node_or_attribute().  
+//! If it returns true, the class is added to the class attribute. The designer's static classes stay in the template.  
+//! Parts of the attribute value can be replaced with the inline placeholders `{{wt_xxx}}`, also in class and style.  
+//!
+//! ```html
+//! <div class="card {{wt_card_color}}" data-wc-selected="wb_is_selected" style="width: {{wt_width}}px;"></div>
+//! ```
+//!
 //! ### Set the event handler  
 //!
 //! Insert an attribute that starts with "data-on-".  
@@ -79,6 +115,163 @@
 //! <input data-on-keyup="wl_nickname_on_keyup" />
 //! ```
 //!
+//! ### Event listener modifiers
+//!
+//! More listeners for the same event are separated by whitespace in the attribute value. They are called in this order.  
+//! The attribute name can have modifiers after the event name, separated by dots:  
+//! `.prevent` calls event.prevent_default(), `.stop` calls event.stop_propagation(),  
+//! `.once` calls the listener only once for the element, also across renders, `.self` only if the event target is the element itself.  
+//! The key modifiers like `.enter`, `.esc`, `.space`, `.up`, `.tab` or a single letter `.a` filter the KeyboardEvent.key.  
+//! `.debounce-300ms` calls the listener only after the events stop for 300 milliseconds, `.throttle-100ms` at most once in 100 milliseconds.  
+//! The duration is in `ms` or `s`. Use them for expensive listeners on input, keyup or scroll. The debounced listener gets the root later from the vdom.  
+//! The `.once` flag and the timers survive the render. With dodrio they are stored with the DOM element, so they follow a keyed item when it moves, and are dropped when the element leaves the document.  
+//! The WebDomWidget keeps them by the element path with the `data-key` or the repeat index, the event and the `fn_name`, and drops them when the listener is not rendered again.  
+//! An unknown modifier is an error in the template.  
+//!
+//! ```html
+//! <form data-on-submit.prevent="wl_save wl_close"></form>
+//! <input data-on-keyup.enter="wl_nickname_on_enter" data-on-keyup.esc="wl_nickname_cancel" />
+//! <input data-on-input.debounce-300ms="wl_search" />
+//! ```
+//!
+//! ### Event listener arguments
+//!
+//! The listener can have arguments in parentheses, so every card of a grid or every sub_template instance can carry its own index.  
+//! The arguments are separated by commas in the template and parsed into the enum ListenerArg: Int, Float, Bool or Str. Quote the text with commas.  
+//! The inline placeholders `{{wt_xxx}}` are replaced inside every argument, so the replaced text cannot add arguments. The replaced argument in quotes is always Str.  
+//! Implement the fn set_event_listener_with_args() for these listeners. Inside `<!--wr_xxx-->` the repeat has the items. The default ignores the arguments and calls set_event_listener() or set_event_listener_in_repeat().  
+//!
+//! ```html
+//! <div class="card" data-on-click="wl_select_card({{wt_card_index}}, 'front')"></div>
+//! ```
+//!
+//! ```ignore
+//! fn set_event_listener_with_args(
+//!     &self,
+//!     fn_name: String,
+//!     args: Vec<ListenerArg>,
+//!     repeat: &[RepeatItem],
+//! ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static> {
+//!     let card_index = args.get(0).and_then(|arg| arg.as_usize()).unwrap_or(0);
+//!     match fn_name.as_str() {
+//!         "wl_select_card" => Box::new(move |root, vdom, _event| {
+//!             let rrc = root.unwrap_mut::<RootRenderingComponent>();
+//!             rrc.selected_card = card_index;
+//!             vdom.schedule_render();
+//!         }),
+//!         _ => self.set_event_listener_in_repeat(fn_name, repeat),
+//!     }
+//! }
+//! ```
+//!
+//! ### Retain or remove the next node  
+//!
+//! Insert a comment that starts with "wb_" (webbrowser boolean).  
+//! After that is the name of the enum to use in the fn retain_next_node_or_attribute().  
+//! If it returns false, the next node is not rendered.  
+//! The comments `<!--wb_else_if wb_xxx-->` and `<!--wb_else-->` can follow the node to render another node when the previous conditions are false.  
+//! The condition of wb_else_if is not called if a previous condition is true.  
+//! The space and the wb_ name after wb_else_if are required, `<!--wb_else_if-->` alone is an error and not a wb_ marker.  
+//!
+//! ```html
+//! <div>
+//!     <!--wb_is_logged_in--><div>Welcome</div>
+//!     <!--wb_else_if wb_is_guest--><div>Guest</div>
+//!     <!--wb_else--><form>Login form</form>
+//! </div>
+//! ```
+//!
+//! ### Repeat the next node
+//!
+//! Insert a comment that starts with "wr_" (webbrowser repeat).  
+//! The next node is rendered once for every item. The fn repeat_count() returns the number of items.  
+//! Inside the repeated node the markers call the methods with the suffix `_in_repeat`.  
+//! They get the slice of RepeatItem with the repeat name and the index of the item. The repeats can be nested.  
+//! The default `_in_repeat` methods call the normal methods, so only the markers that depend on the item must be implemented.  
+//!
+//! ```html
+//! <div><!--wr_items--><div class="item"><!--wt_item_name-->name</div></div>
+//! ```
+//!
+//! ```ignore
+//! fn repeat_count(&self, fn_name: &str, _repeat: &[RepeatItem]) -> usize {
+//!     match fn_name {
+//!         "wr_items" => self.items.len(),
+//!         _ => 0,
+//!     }
+//! }
+//! fn replace_with_string_in_repeat(&self, fn_name: &str, repeat: &[RepeatItem]) -> String {
+//!     match fn_name {
+//!         "wt_item_name" => self.items[repeat[0].index].name.clone(),
+//!         _ => self.replace_with_string(fn_name),
+//!     }
+//! }
+//! ```
+//!
+//! ### Keyed children
+//!
+//! Reordering a list without keys rebuilds the DOM nodes and loses the input focus and the CSS transitions.  
+//! The attribute `data-key` sets the key of the element for the dodrio diff. It is not rendered as an attribute.  
+//! The value can have inline placeholders `{{wt_xxx}}` or be replaced with `data-wt-key`. A number is used as it is, other text is hashed.  
+//! Set the key on the repeated node or on the root element of the sub_template used in replace_with_nodes().  
+//! render_template() and the generated code return an error if a key is used twice among the siblings. The error has the template name, the line and the column of the second data-key.  
+//! The keys are compared as text, so "01" and "1" are different keys. If two keys get the same dodrio key from the hash, the next free dodrio key is used.  
+//!
+//! ```html
+//! <div><!--wr_players--><div class="card" data-key="{{wt_player_id}}"><!--wt_player_name-->name</div></div>
+//! ```
+//!
+//! ### Svg and MathML
+//!
+//! The elements inside `<svg>` get the svg namespace and the elements inside `<math>` the MathML namespace.  
+//! The children of `<foreignObject>` are html again. The namespace is the enum HtmlOrSvg: Html, Svg or MathMl.  
+//! Dodrio sets the attributes without namespace, so `xlink:href` is rendered as the SVG 2 `href` and `xml:lang` as `lang`.  
+//! The declarations `xmlns` and `xmlns:xxx` are not rendered, the namespace comes from the element.  
+//!
+//! ```html
+//! <svg xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="#icon_star"/></svg>
+//! <math><mfrac><mi>a</mi><mn>2</mn></mfrac></math>
+//! ```
+//!
+//! ### Attribute macro for the trait methods
+//!
+//! Instead of writing the big `match fn_name {...}` blocks in the trait methods, annotate the methods with the marker names and use the attribute macro `#[html_templating]` on the implementation of the trait.  
+//!
+//! ```ignore
+//! use rust_wasm_dodrio_templating::html_templating;
+//!
+//! #[html_templating]
+//! impl HtmlTemplating for RootRenderingComponent {
+//!     // the marker wt_nick_name
+//!     #[wt]
+//!     fn nick_name(&self) -> String {
+//!         self.nick_name.clone()
+//!     }
+//!     // explicit names, more names for the same method
+//!     #[wb("wb_is_visible")]
+//!     #[wb("wb_is_shown")]
+//!     fn is_visible(&self) -> bool {
+//!         true
+//!     }
+//!     #[wn]
+//!     fn grid_items<'a>(&self, cx: &mut RenderContext<'a>) -> Vec<Node<'a>> {
+//!         vec![]
+//!     }
+//!     // the listener has no self, because the closure outlives the render
+//!     #[wl]
+//!     fn click(root: &mut dyn RootRender, vdom: VdomWeak, event: web_sys::Event) {}
+//!     // other trait methods stay as they are
+//!     fn repeat_count(&self, fn_name: &str, repeat: &[RepeatItem]) -> usize {
+//!         self.items.len()
+//!     }
+//! }
+//! ```
+//!
+//! The macro generates replace_with_string(), retain_next_node_or_attribute(), replace_with_nodes() and set_event_listener(). The unknown names return an error text, true, no nodes and an empty listener.  
+//! Duplicate marker names and names without the correct prefix are compile errors.  
+//! The generated code uses `dodrio` and `web_sys` re-exported from `rust_wasm_dodrio_templating`, so it compiles also where the project names them differently.  
+//! The list of all the marker names is in the constant `RootRenderingComponent::MARKER_NAMES` and in the trait method `marker_names()`.  
+//!
 //! ### Sub_templates
 //!
 //! When a part of the html template needs to be repeated, we use sub_templates.
@@ -91,22 +284,28 @@
 //! ```
 //!
 //! The sub_template has a name attribute that is used for replacement in Rust code to return a vector of nodes for replace the "wn_" special comment.  
+//! The `TemplateLibrary` parses the html template once and splits out all the named sub_templates, also the nested ones. They are removed from the main template. A `<template>` without the name attribute stays in the main template.  
+//! Every sub_template must have exactly one root element.  
 //!
 //! ```ignore
+//! // once, when the html template is loaded
+//! let template_library = TemplateLibrary::parse_named("index.html", &html_template)?;
+//! // the names of all sub_templates
+//! let names = template_library.names();
+//!
 //! pub fn div_grid_all_items<'a>(
 //!     rrc: &RootRenderingComponent,
 //!     cx: &mut RenderContext<'a>,
 //! ) -> Vec<Node<'a>> {
 //!     let mut vec_grid_items: Vec<Node<'a>> = Vec::new();
 //!     for x in 1..=10 {
-//!         let html_template = rrc.web_data.get_sub_template("sub_template_name");
-//!
-//!         let grid_item = rrc.render_template(
+//!         let grid_item = rrc.render_sub_template(
 //!             cx,
-//!             &html_template,
+//!             &rrc.template_library,
+//!             "sub_template_name",
 //!             rust_wasm_dodrio_templating::html_template_mod::HtmlOrSvg::Html
 //!         ).unwrap();
-//!         vec_grid_items.push(grid_item_bump);
+//!         vec_grid_items.push(grid_item);
 //!     }
 //!
 //!     // return
@@ -114,6 +313,251 @@
 //! }
 //! ```
 //!
+//! The main template without the sub_templates is rendered with `render_parsed(cx, template_library.main_template(), HtmlOrSvg::Html)`.  
+//!
+//! ### Include other templates
+//!
+//! Shared headers, footers and dialogs are written only once in their own template.  
+//! The comment that starts with "wi_" (webbrowser include) is replaced with the root element of the included template.  
+//!
+//! ```html
+//! <div><!--wi_header--><p>the page content</p><!--wi_footer--></div>
+//! ```
+//!
+//! The templates are found by name in a `TemplateSource`. It is implemented for `HashMap<String, String>` with the html templates by name and for the `TemplateLibrary` with its sub_templates.  
+//! The fn render_template() resolves the includes with the source from the trait method `template_source()`. For parsed templates call `resolve_includes()` once after parsing.  
+//! The included templates can include other templates. A template that includes itself, directly or through other templates, is an error. The error message contains the chain of includes like `index -> header -> menu`.  
+//! The code generator resolves the includes with the other templates in the same build, where the include name is the function name.  
+//!
+//! ```ignore
+//! fn template_source(&self) -> Option<&dyn TemplateSource> {
+//!     Some(&self.templates)
+//! }
+//! ```
+//!
+//! ### Layouts with named blocks
+//!
+//! The pages of a project share the same outer page chrome. It is written only once in the layout template.  
+//! The layout marks the replaceable parts with the attribute `data-block="block_name"`. The content of the element is the default.  
+//!
+//! ```html
+//! <html><body>
+//!     <header data-block="header">My project</header>
+//!     <main class="content" data-block="content"><p>default content</p></main>
+//! </body></html>
+//! ```
+//!
+//! The page declares its layout with the attribute `data-layout="layout_name"` on the root element and contains only the blocks it overrides.  
+//!
+//! ```html
+//! <div data-layout="layout">
+//!     <div data-block="content"><p>The page content.</p></div>
+//! </div>
+//! ```
+//!
+//! The element of the layout stays with its attributes, only the content of the block is replaced. The root element of the page is not rendered.  
+//! The layout can have its own layout. A block in the page that does not exist in the layout is an error.  
+//! The layouts come from the same `TemplateSource` as the includes. The fn render_template() resolves the layout before the includes. For parsed templates call `resolve_layout()` once after parsing.  
+//!
+//! ### Parse once, render many times
+//!
+//! The fn render_template() parses the html template on every render.  
+//! For templates that are rendered often, parse the template only once with `ParsedTemplate::parse()` and keep it in the RootRenderingComponent.  
+//! The fn render_parsed() walks the parsed tree into dodrio nodes without tokenizing the html again.  
+//!
+//! ```ignore
+//! // once, when the html template is loaded
+//! let parsed_template = ParsedTemplate::parse(&html_template)?;
+//! // on every render
+//! let node = rrc.render_parsed(cx, &rrc.parsed_template, HtmlOrSvg::Html)?;
+//! ```
+//!
+//! ### Errors in the template
+//!
+//! The functions return `TemplateError` when the html template is broken.  
+//! It contains the kind of error, the template name, the line and column and the path of elements to the failure point.  
+//! Use `ParsedTemplate::parse_named()` to have the template name in the error message:  
+//!
+//! ```text
+//! index.html:2:9: Error: End element not correct: starts <p> ends </span> in <div> <p>
+//! ```
+//!
+//! ### Html entities
+//!
+//! The templates exported from design tools often contain html entities like `&nbsp;`, `&copy;`, `&#8364;` or `&#x2014;`.  
+//! All html5 named entities and the decimal and hex numeric references are decoded in the text nodes and attribute values. The same decoding is used for the strings returned from `replace_with_string()`.  
+//! Unknown entities and entities without the ending semicolon stay in the text as they are.  
+//! To keep the old minimal decoding of only the 5 xml control characters `&quot; &apos; &amp; &lt; &gt;` implement the trait method:  
+//!
+//! ```ignore
+//! fn entity_decoding(&self) -> EntityDecoding {
+//!     EntityDecoding::Minimal
+//! }
+//! ```
+//!
+//! Use the same option for the code generator with `generate_rust_code_with_decoding()` and for `ParsedTemplate::parse_with_decoding()`.  
+//!
+//! ### Server side rendering
+//!
+//! The same templates and the same HtmlTemplating implementation can render to an html string without RenderContext and without wasm.  
+//! It is used natively on the server to pre-render the page for the first paint or for the crawlers.  
+//! The markers work the same. The listeners are dropped. The text and the attribute values are escaped.  
+//! The text inside `<script>` and `<style>` is not escaped, only `</script` and `</style` in any case become `<\/script` and `<\/style`, so a replaced string cannot close the element.  
+//! The marker `<!--wn_xxx-->` calls replace_with_html() instead of replace_with_nodes(). The default returns an empty string.  
+//! Use render_sub_template_to_string() inside it for the sub_templates.  
+//!
+//! ```ignore
+//! let html = rrc.render_template_to_string(&html_template, HtmlOrSvg::Html)?;
+//! ```
+//!
+//! ```ignore
+//! fn replace_with_html(&self, fn_name: &str) -> String {
+//!     match fn_name {
+//!         "wn_player_list" => self.players.iter().map(|_| self.render_sub_template_to_string(&self.library, "player", HtmlOrSvg::Html).unwrap()).collect(),
+//!         _ => String::new(),
+//!     }
+//! }
+//! ```
+//!
+//! ### Hydration
+//!
+//! For hydration the server renders with `render_template_for_hydration()`. The elements retained by the `wb_` markers get the attribute `data-wm="wb_xxx"`
+//! and the html from replace_with_html() is between the comments `<!--wn_xxx-->` and `<!--/wn_xxx-->`.  
+//! In the browser `hydrate()` compares the existing DOM with the template output and returns the mismatches, usually because the state on the server was different.  
+//! Then it adopts the existing DOM: the walker goes through the elements from the server and only attaches the `data-on-` listeners. The nodes stay in the page.  
+//! Dodrio cannot adopt existing nodes, so the hydrated page is a `WebDomWidget` and the rrc implements `WebDomListener`. When the DOM does not match, the widget renders the template fresh.  
+//!
+//! ```ignore
+//! let (widget, mismatches) = hydrate(&div_for_widget, rrc, &html_template)?;
+//! for mismatch in &mismatches {
+//!     web_sys::console::log_1(&mismatch.to_string().into());
+//! }
+//! ```
+//!
+//! ### Render targets
+//!
+//! The markers are evaluated by one walker through the parsed template. It drives the trait `RenderTarget`: begin element, attribute, key, listener, text, the children from `<!--wn_xxx-->` and end element.  
+//! `DodrioRenderTarget` builds the dodrio Node for `render_parsed()` and `HtmlNodeTarget` builds the tree for the html string. A test backend or a different vdom implements only `RenderTarget`, the marker logic stays the same.  
+//! The listeners come to the backend as `ListenerMarker` with the event, fn_name, arguments and modifiers. The `<!--wn_xxx-->` comes as `SpliceMarker`. Their methods `listener_box()`, `nodes()` and `html()` choose the plain or the `_in_repeat` method of the rrc, so the backends never call the rrc directly.  
+//!
+//! ```ignore
+//! let node = render_to_target(rrc, &parsed_template, HtmlOrSvg::Html, HtmlNodeTarget::new(false))?;
+//! ```
+//!
+//! ### Web DOM widgets without dodrio
+//!
+//! For static widgets embedded in pages not made with dodrio, `WebDomWidget` renders the template straight into a `web_sys::Element` with `create_element_ns()`. There is no Vdom in the page and the markers are the same.  
+//! The `data-on-` listeners are wasm_bindgen Closures, kept alive in `WebDomFragment` and removed when it is dropped. There is no `VdomWeak`, so the rrc implements the trait `WebDomListener` instead of `set_event_listener()`. When `on_event()` returns true, the widget renders again in the next animation frame.  
+//! The marker `<!--wn_xxx-->` calls `replace_with_html()` like on the server, because there is no `RenderContext`. The modifiers work like in dodrio, the `.debounce` timer calls `on_event()` later.  
+//!
+//! ```ignore
+//! impl WebDomListener for RootRenderingComponent {
+//!     fn on_event(&mut self, fn_name: &str, _args: &[ListenerArg], _repeat: &[RepeatItem], _event: web_sys::Event) -> bool {
+//!         match fn_name {
+//!             "wl_increment" => {
+//!                 self.counter += 1;
+//!                 true
+//!             }
+//!             _ => false,
+//!         }
+//!     }
+//! }
+//!
+//! let widget = WebDomWidget::new(&div_for_widget, rrc, &html_template)?;
+//! widget.rrc().borrow_mut().counter += 1;
+//! widget.render()?;
+//! ```
+//!
+//! ### Test harness
+//!
+//! The templates can be tested natively with `cargo test`, without the browser and without the dodrio RenderContext. `render_for_test()` renders the template into a tree of `TestElement` that can be queried by tag, attribute and text.  
+//! `MockTemplating` returns the values from its HashMaps and remembers the called markers in order, also the `wl_` listeners with their arguments and repeat indexes. The project can render also its own RootRenderingComponent, then `<!--wn_xxx-->` calls `replace_with_html()`.  
+//!
+//! ```ignore
+//! let mut mock = MockTemplating::default();
+//! mock.strings.insert("wt_name".to_owned(), "Tom".to_owned());
+//! mock.booleans.insert("wb_is_admin".to_owned(), false);
+//! let tree = render_for_test(&mock, &html_template)?;
+//! assert_eq!(tree.find("h1").unwrap().text(), "Hello Tom");
+//! assert!(tree.find_by_attribute("class", "admin").is_empty());
+//! assert_eq!(mock.call_names(), vec!["wt_name", "wb_is_admin"]);
+//! ```
+//!
+//! ### Code generator
+//!
+//! In `build.rs` the module `codegen_mod` reads the html template files and writes the Rust code for the dodrio nodes.  
+//! Every template becomes a function `fn(rrc: &R, cx: &mut RenderContext<'a>) -> Result<Node<'a>, TemplateError>` where R implements HtmlTemplating.  
+//! The only error in runtime is a `data-key` used twice among the siblings, the same as in render_template().  
+//! The generated code calls the same trait methods for the same special comments and attributes.  
+//! The template errors are found in `build.rs`, not in runtime.  
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_file = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("templates.rs");
+//!     rust_wasm_dodrio_templating::codegen_mod::write_rust_code_for_files(
+//!         &out_file,
+//!         &[("render_index", std::path::Path::new("web_server_folder/index.html"))],
+//!     )
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! The dev build uses the live-editable html template and the release build uses the generated code:  
+//!
+//! ```ignore
+//! #[cfg(not(debug_assertions))]
+//! include!(concat!(env!("OUT_DIR"), "/templates.rs"));
+//!
+//! fn render<'a>(&self, cx: &mut RenderContext<'a>) -> Node<'a> {
+//!     #[cfg(debug_assertions)]
+//!     return self.render_template(cx, &self.html_template, HtmlOrSvg::Html).unwrap();
+//!     #[cfg(not(debug_assertions))]
+//!     return render_index(self, cx).unwrap();
+//! }
+//! ```
+//!
+//! ### Template lint
+//!
+//! Typos in the markers like `<!--wt_nick_nmae-->` are found only in runtime, when replace_with_string() falls into the catch-all match arm.  
+//! The command line lint lists every marker in the templates with its position and compares the names with the names implemented in the Rust code.  
+//! The names come from a manifest file with one name per line or from the string literals in the Rust source files like the match arms `"wt_nick_name" =>`. The methods with the derive attributes `#[wt]`, `#[wb]`, `#[wn]` and `#[wl]` are found too, with the prefix added to the method name like in the derive.  
+//!
+//! ```bash
+//! cargo install rust_wasm_dodrio_templating --bin template_lint
+//! template_lint --rust src templates/index.html
+//! ```
+//!
+//! It reports the malformed markers like `data-wt-href="href"` without the "wt_" prefix, the unknown markers that are not implemented in Rust and the unused names that are not in any template.  
+//! The exit code is 1 if there are malformed or unknown markers, so it can run in the build scripts.  
+//! The same functions are in the module `template_lint_mod`.  
+//!
+//! ### Typed marker stubs
+//!
+//! When the designer adds new markers, the Rust code must implement them.  
+//! The module `marker_stubs_mod` generates a Rust trait with one method for every marker in the templates. The doc comment of the method tells the kind (text, attribute, nodes, boolean, listener, repeat) and the position in the template.  
+//! The RootRenderingComponent implements the generated trait and the compiler tells which markers are missing.  
+//!
+//! ```ignore
+//! // build.rs
+//! rust_wasm_dodrio_templating::marker_stubs_mod::write_marker_trait_for_files(
+//!     &out_dir.join("markers.rs"),
+//!     "IndexMarkers",
+//!     &[Path::new("templates/index.html")],
+//! )?;
+//! ```
+//!
+//! The same trait is printed by the command `template_lint --stubs IndexMarkers templates/index.html`.  
+//! The provided methods like `markers_replace_with_string()` of the generated trait do the dispatch by the marker name and return None for unknown names:  
+//!
+//! ```ignore
+//! fn replace_with_string(&self, fn_name: &str) -> String {
+//!     self.markers_replace_with_string(fn_name).unwrap_or_default()
+//! }
+//! ```
+//!
+//! The method names are the marker names, so they work also with the attribute macro: `#[wt] fn wt_nick_name()` is the marker `wt_nick_name`.  
+//!
 //! ## cargo crev reviews and advisory
 //!
 //! It is recommended to always use [cargo-crev](https://github.com/crev-dev/cargo-crev)  
@@ -124,4 +568,24 @@
 //!
 // endregion: lmake_md_to_doc_comments include README.md A //!
 
+pub mod codegen_mod;
+pub mod event_listener_mod;
+pub mod html_entity_mod;
 pub mod html_template_mod;
+pub mod hydration_mod;
+pub mod marker_stubs_mod;
+pub mod parsed_template_mod;
+pub mod render_target_mod;
+pub mod server_render_mod;
+pub mod template_error_mod;
+pub mod template_include_mod;
+pub mod template_layout_mod;
+pub mod template_library_mod;
+pub mod template_lint_mod;
+pub mod test_harness_mod;
+pub mod web_dom_mod;
+
+pub use rust_wasm_dodrio_templating_derive::html_templating;
+// the code from #[html_templating] uses these paths, the project does not need the same versions
+pub use dodrio;
+pub use web_sys;
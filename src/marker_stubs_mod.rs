@@ -0,0 +1,226 @@
+//! **marker_stubs_mod**
+//! When the designer adds new markers to the template, the Rust code must implement them.
+//! This module generates a Rust trait with one method for every marker found in the templates.
+//! The RootRenderingComponent implements the generated trait and
+//! the compiler tells which markers are missing.
+//! The provided methods of the generated trait do the dispatch by the marker name.
+
+// region: use
+use crate::template_lint_mod::*;
+use std::fmt::Write;
+use unwrap::unwrap;
+// endregion: use
+
+/// Generates the Rust code of the trait with all the markers in the templates.
+/// The input is a slice of tuples (template name, html template).
+/// The malformed markers and the names that are not Rust identifiers are errors.
+pub fn generate_marker_trait(
+    trait_name: &str,
+    templates: &[(&str, &str)],
+) -> Result<String, String> {
+    let mut markers: Vec<MarkerLocation> = vec![];
+    let mut errors: Vec<String> = vec![];
+    for (template_name, html_template) in templates {
+        let lint = lint_template(template_name, html_template);
+        errors.extend(lint.errors.iter().map(|err| err.to_string()));
+        markers.extend(lint.markers);
+    }
+    for marker in &markers {
+        if !is_rust_identifier(&marker.name) {
+            errors.push(format!("{}: not a Rust identifier", marker));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    // one method for every name, in the order of the first use
+    let mut names: Vec<&str> = vec![];
+    for marker in &markers {
+        if !names.contains(&marker.name.as_str()) {
+            names.push(&marker.name);
+        }
+    }
+
+    let mut code = String::new();
+    code.push_str("// Generated by rust_wasm_dodrio_templating::marker_stubs_mod.\n");
+    code.push_str("// Do not edit. Change the html template and rebuild.\n\n");
+    unwrap!(writeln!(
+        code,
+        "/// The markers in the templates: {}  \n\
+        /// Implement this trait for the RootRenderingComponent.\n\
+        #[allow(unused_variables, clippy::all)]\n\
+        pub trait {} {{",
+        templates
+            .iter()
+            .map(|(template_name, _)| *template_name)
+            .collect::<Vec<&str>>()
+            .join(", "),
+        trait_name
+    ));
+    for name in &names {
+        let uses: Vec<String> = markers
+            .iter()
+            .filter(|marker| marker.name == *name)
+            .map(|marker| {
+                format!(
+                    "{} {}:{}:{}",
+                    marker.kind, marker.template_name, marker.line, marker.column
+                )
+            })
+            .collect();
+        unwrap!(writeln!(code, "    /// {}", uses.join(", ")));
+        unwrap!(writeln!(code, "    {};", method_signature(name)));
+    }
+    code.push_str(&dispatch_code(&names));
+    code.push_str("}\n");
+    // return
+    Ok(code)
+}
+
+/// Helper for `build.rs`: reads the html template files,
+/// generates the trait and writes it in the `out_file`.
+/// The input is a slice of paths of the html template files.
+/// It prints `cargo:rerun-if-changed` for every template file.
+pub fn write_marker_trait_for_files(
+    out_file: &std::path::Path,
+    trait_name: &str,
+    template_files: &[&std::path::Path],
+) -> Result<(), String> {
+    let mut templates = Vec::new();
+    for path in template_files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let html_template = std::fs::read_to_string(path)
+            .map_err(|err| format!("Error: cannot read {}: {}", path.display(), err))?;
+        templates.push((path.display().to_string(), html_template));
+    }
+    let templates: Vec<(&str, &str)> = templates
+        .iter()
+        .map(|(name, html_template)| (name.as_str(), html_template.as_str()))
+        .collect();
+    let code = generate_marker_trait(trait_name, &templates)?;
+    std::fs::write(out_file, code)
+        .map_err(|err| format!("Error: cannot write {}: {}", out_file.display(), err))
+}
+
+/// The signature depends only on the prefix,
+/// the same name can be used as text and as attribute.
+fn method_signature(name: &str) -> String {
+    if name.starts_with("wn_") {
+        format!(
+            "fn {}<'a>(&self, cx: &mut ::rust_wasm_dodrio_templating::dodrio::RenderContext<'a>) -> Vec<::rust_wasm_dodrio_templating::dodrio::Node<'a>>",
+            name
+        )
+    } else if name.starts_with("wb_") {
+        format!("fn {}(&self) -> bool", name)
+    } else if name.starts_with("wr_") {
+        format!(
+            "fn {}(&self, repeat: &[::rust_wasm_dodrio_templating::html_template_mod::RepeatItem]) -> usize",
+            name
+        )
+    } else if name.starts_with("wl_") {
+        format!(
+            "fn {}(root: &mut dyn ::rust_wasm_dodrio_templating::dodrio::RootRender, vdom: ::rust_wasm_dodrio_templating::dodrio::VdomWeak, event: ::rust_wasm_dodrio_templating::web_sys::Event)",
+            name
+        )
+    } else {
+        format!("fn {}(&self) -> String", name)
+    }
+}
+
+/// The provided methods for the HtmlTemplating implementation.
+/// They return None for the names that are not in the templates.
+fn dispatch_code(names: &[&str]) -> String {
+    let arms = |prefix: &str, call: &dyn Fn(&str) -> String| -> String {
+        names
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| format!("            {:?} => Some({}),\n", name, call(name)))
+            .collect()
+    };
+    let mut code = String::new();
+    unwrap!(write!(
+        code,
+        "\n    /// dispatch for replace_with_string()\n    \
+        fn markers_replace_with_string(&self, fn_name: &str) -> Option<String> {{\n        \
+        match fn_name {{\n{}            _ => None,\n        }}\n    }}\n",
+        arms("wt_", &|name| format!("self.{}()", name))
+    ));
+    unwrap!(write!(
+        code,
+        "\n    /// dispatch for retain_next_node_or_attribute()\n    \
+        fn markers_retain_next_node_or_attribute(&self, fn_name: &str) -> Option<bool> {{\n        \
+        match fn_name {{\n{}            _ => None,\n        }}\n    }}\n",
+        arms("wb_", &|name| format!("self.{}()", name))
+    ));
+    unwrap!(write!(
+        code,
+        "\n    /// dispatch for replace_with_nodes()\n    \
+        fn markers_replace_with_nodes<'a>(&self, cx: &mut ::rust_wasm_dodrio_templating::dodrio::RenderContext<'a>, fn_name: &str) -> Option<Vec<::rust_wasm_dodrio_templating::dodrio::Node<'a>>> {{\n        \
+        match fn_name {{\n{}            _ => None,\n        }}\n    }}\n",
+        arms("wn_", &|name| format!("self.{}(cx)", name))
+    ));
+    unwrap!(write!(
+        code,
+        "\n    /// dispatch for repeat_count()\n    \
+        fn markers_repeat_count(&self, fn_name: &str, repeat: &[::rust_wasm_dodrio_templating::html_template_mod::RepeatItem]) -> Option<usize> {{\n        \
+        match fn_name {{\n{}            _ => None,\n        }}\n    }}\n",
+        arms("wr_", &|name| format!("self.{}(repeat)", name))
+    ));
+    unwrap!(write!(
+        code,
+        "\n    /// dispatch for set_event_listener()\n    \
+        fn markers_set_event_listener(&self, fn_name: &str) -> Option<Box<dyn Fn(&mut dyn ::rust_wasm_dodrio_templating::dodrio::RootRender, ::rust_wasm_dodrio_templating::dodrio::VdomWeak, ::rust_wasm_dodrio_templating::web_sys::Event) + 'static>>\n    \
+        where\n        Self: Sized + 'static,\n    {{\n        \
+        match fn_name {{\n{}            _ => None,\n        }}\n    }}\n",
+        arms("wl_", &|name| format!("Box::new(Self::{})", name))
+    ));
+    // return
+    code
+}
+
+/// the marker name becomes the method name
+fn is_rust_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        && !name.starts_with(|ch: char| ch.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trait_has_one_method_per_name_and_the_dispatch() {
+        let code = unwrap!(generate_marker_trait(
+            "CardMarkers",
+            &[(
+                "card",
+                r#"<div title="{{wt_title}}"><!--wt_title-->t<!--wb_show--><b data-on-click="wl_pick">b</b><ul><!--wr_items--><li>i</li></ul></div>"#
+            )],
+        ));
+        assert!(code.contains("pub trait CardMarkers {"));
+        assert_eq!(code.matches("    fn wt_title(&self) -> String;").count(), 1);
+        assert!(code.contains("fn wb_show(&self) -> bool;"));
+        assert!(code.contains("fn wr_items(&self, repeat: "));
+        assert!(code.contains("fn wl_pick(root: "));
+        assert!(code.contains(r#""wt_title" => Some(self.wt_title()),"#));
+        assert!(code.contains(r#""wl_pick" => Some(Box::new(Self::wl_pick)),"#));
+        // only the absolute paths to the re-exported crates
+        let bare = code
+            .replace("::rust_wasm_dodrio_templating::dodrio::", "")
+            .replace("::rust_wasm_dodrio_templating::web_sys::", "");
+        assert!(!bare.contains("dodrio::") && !bare.contains("web_sys::"));
+    }
+
+    #[test]
+    fn bad_names_are_errors() {
+        let err = unwrap!(generate_marker_trait(
+            "CardMarkers",
+            &[("card", "<div><!--wt_a-b-->x</div>")]
+        )
+        .err());
+        assert!(err.contains("not a Rust identifier"));
+    }
+}
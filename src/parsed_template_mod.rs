@@ -0,0 +1,697 @@
+//! **parsed_template_mod**
+//! The html template is parsed only once into a tree of elements, attributes, text and markers.
+//! This ParsedTemplate can be rendered many times without tokenizing the html again.
+//! The special comments and "data-" attributes become typed marker nodes.
+
+// region: use
+use crate::event_listener_mod::split_listener_args;
+use crate::html_entity_mod::*;
+use crate::html_template_mod::ATTR_KEY;
+use crate::template_error_mod::*;
+use reader_for_microxml::*;
+use unwrap::unwrap;
+// endregion: use
+
+/// The html template parsed once, ready to be rendered many times.
+#[derive(Clone, Debug)]
+pub struct ParsedTemplate {
+    /// the root element of the template
+    pub root: TemplateElement,
+}
+
+/// Element with attributes and child nodes.
+#[derive(Clone, Debug)]
+pub struct TemplateElement {
+    /// tag name
+    pub name: String,
+    /// attributes and attribute markers in the same order as in the template
+    pub attributes: Vec<TemplateAttribute>,
+    /// child nodes and node markers in the same order as in the template
+    pub children: Vec<TemplateNode>,
+    /// the position of data-key for the DuplicateKey error
+    pub key_position: Option<TemplatePosition>,
+}
+
+/// Attributes can be static or markers.
+#[derive(Clone, Debug)]
+pub enum TemplateAttribute {
+    /// static attribute, the value is already decoded
+    Static { name: String, value: String },
+    /// data-wt-xxx="wt_xxx" replaces the value of the next attribute
+    /// or the next text node
+    ReplaceString { name: String, fn_name: String },
+    /// data-wb-xxx="wb_xxx" retains or removes the next attribute
+    RetainBoolean { name: String, fn_name: String },
+    /// data-wc-xxx="wb_xxx" adds the class xxx to the class attribute if true
+    ToggleClass { class_name: String, fn_name: String },
+    /// data-on-xxx.modifier="wl_xxx" sets the event listener.
+    /// There can be more listeners for the same event.
+    /// The arguments data-on-click="wl_xxx(17, {{wt_index}})" are split here
+    /// and the placeholders are replaced inside every argument in the render.
+    Listener {
+        event: String,
+        fn_name: String,
+        modifiers: Vec<ListenerModifier>,
+        args: Option<Vec<TemplateListenerArg>>,
+    },
+    /// attribute value with inline placeholders like class="card {{wt_state}}"
+    Interpolated { name: String, parts: Vec<TextPart> },
+}
+
+/// One argument of wl_xxx(17, {{wt_name}}), split before the placeholders are replaced,
+/// so the replaced text cannot change the number of arguments.
+#[derive(Clone, Debug)]
+pub enum TemplateListenerArg {
+    /// the argument without placeholders, parsed in the render with ListenerArg::parse()
+    Static(String),
+    /// the argument with placeholders. The replaced argument in quotes is always text.
+    Interpolated { parts: Vec<TextPart>, quoted: bool },
+}
+
+/// Modifiers in the attribute name like data-on-click.prevent.stop
+/// run in the generic wrapper before the listener.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListenerModifier {
+    /// .prevent calls event.prevent_default()
+    Prevent,
+    /// .stop calls event.stop_propagation()
+    Stop,
+    /// .once calls the listener only once for the element, also across renders
+    Once,
+    /// .self calls the listener only if the event target is the element itself
+    SelfOnly,
+    /// .enter, .esc, .a and other keys call the listener only for this KeyboardEvent.key
+    Key(String),
+    /// .debounce-300ms calls the listener after the events stop for this milliseconds
+    Debounce(u32),
+    /// .throttle-100ms calls the listener at most once in this milliseconds
+    Throttle(u32),
+}
+
+impl ListenerModifier {
+    /// None if the modifier is not recognized
+    pub fn parse(modifier: &str) -> Option<ListenerModifier> {
+        if let Some(duration) = modifier.strip_prefix("debounce-") {
+            return parse_milliseconds(duration).map(ListenerModifier::Debounce);
+        }
+        if let Some(duration) = modifier.strip_prefix("throttle-") {
+            return parse_milliseconds(duration).map(ListenerModifier::Throttle);
+        }
+        let key = match modifier {
+            "prevent" => return Some(ListenerModifier::Prevent),
+            "stop" => return Some(ListenerModifier::Stop),
+            "once" => return Some(ListenerModifier::Once),
+            "self" => return Some(ListenerModifier::SelfOnly),
+            "enter" => "Enter",
+            "tab" => "Tab",
+            "delete" => "Delete",
+            "backspace" => "Backspace",
+            "esc" | "escape" => "Escape",
+            "space" => " ",
+            "up" => "ArrowUp",
+            "down" => "ArrowDown",
+            "left" => "ArrowLeft",
+            "right" => "ArrowRight",
+            "home" => "Home",
+            "end" => "End",
+            "pageup" => "PageUp",
+            "pagedown" => "PageDown",
+            // a single letter or digit
+            _ if modifier.chars().count() == 1
+                && modifier.chars().all(|ch| ch.is_ascii_alphanumeric()) =>
+            {
+                modifier
+            }
+            _ => return None,
+        };
+        // return
+        Some(ListenerModifier::Key(key.to_owned()))
+    }
+}
+
+/// 300ms or 2s in milliseconds
+fn parse_milliseconds(duration: &str) -> Option<u32> {
+    if let Some(milliseconds) = duration.strip_suffix("ms") {
+        milliseconds.parse().ok()
+    } else if let Some(seconds) = duration.strip_suffix('s') {
+        seconds.parse::<u32>().ok()?.checked_mul(1000)
+    } else {
+        None
+    }
+}
+
+/// Child nodes can be elements, text or markers.
+#[derive(Clone, Debug)]
+pub enum TemplateNode {
+    /// child element
+    Element(TemplateElement),
+    /// static text, already decoded
+    Text(String),
+    /// text with inline placeholders like Hello {{wt_name}}!
+    InterpolatedText(Vec<TextPart>),
+    /// <!--wt_xxx--> replaces the next text node
+    ReplaceString(String),
+    /// <!--wn_xxx--> replaces the next element with nodes
+    ReplaceNodes(String),
+    /// <!--wb_xxx--> retains or removes the next element
+    RetainBoolean(String),
+    /// <!--wr_xxx--> repeats the next element for every item
+    Repeat(String),
+    /// <!--wb_else_if wb_xxx--> retains the next element
+    /// if the previous conditions are false and this is true
+    ElseIf(String),
+    /// <!--wb_else--> retains the next element if the previous conditions are false
+    Else,
+    /// <!--wi_xxx--> is replaced with the root element of the template xxx
+    /// from the TemplateSource
+    Include(String),
+}
+
+/// Part of a text node or attribute value with inline placeholders.
+#[derive(Clone, Debug)]
+pub enum TextPart {
+    /// static text, already decoded
+    Static(String),
+    /// {{wt_xxx}} is replaced with replace_with_string()
+    ReplaceString(String),
+}
+
+impl ParsedTemplate {
+    /// Parses the html template.
+    /// The first element must be the root element.
+    pub fn parse(html_template: &str) -> Result<ParsedTemplate, TemplateError> {
+        Self::parse_named("", html_template)
+    }
+
+    /// Parses the html template.
+    /// The name of the template is used in the error messages.
+    pub fn parse_named(
+        template_name: &str,
+        html_template: &str,
+    ) -> Result<ParsedTemplate, TemplateError> {
+        Self::parse_with_decoding(template_name, html_template, EntityDecoding::default())
+    }
+
+    /// Parses the html template.
+    /// The static text and attribute values are decoded as defined by entity_decoding.
+    pub fn parse_with_decoding(
+        template_name: &str,
+        html_template: &str,
+        entity_decoding: EntityDecoding,
+    ) -> Result<ParsedTemplate, TemplateError> {
+        if html_template.is_empty() {
+            // the reader_for_microxml does not accept an empty string
+            return Err(TemplateError::new(
+                TemplateErrorKind::NoRootElement,
+                template_name,
+                html_template,
+                0,
+                &[],
+            ));
+        }
+        let mut parser = TemplateParser {
+            template_name,
+            html_template,
+            reader_for_microxml: ReaderForMicroXml::new(html_template),
+            dom_path: vec![],
+            last_offset: 0,
+            entity_decoding,
+        };
+        // the first element must be root and is special
+        match parser.next_token()? {
+            Some(Token::StartElement(name)) => {
+                parser.dom_path.push(name.to_owned());
+                let root = parser.parse_element(name)?;
+                // return
+                Ok(ParsedTemplate { root })
+            }
+            _ => Err(parser.error(TemplateErrorKind::NoRootElement)),
+        }
+    }
+}
+
+/// The parser keeps the position and the dom_path for the error messages.
+struct TemplateParser<'t> {
+    template_name: &'t str,
+    html_template: &'t str,
+    reader_for_microxml: ReaderForMicroXml<'t>,
+    dom_path: Vec<String>,
+    /// byte offset of the last token in the html template
+    last_offset: usize,
+    entity_decoding: EntityDecoding,
+}
+
+impl<'t> TemplateParser<'t> {
+    /// the next token or the parser error with position.
+    /// None is the end of the template.
+    fn next_token(&mut self) -> Result<Option<Token<'t>>, TemplateError> {
+        match self.reader_for_microxml.next() {
+            None => Ok(None),
+            Some(Err(e)) => Err(self.error(TemplateErrorKind::ParserError(
+                e.trim_start_matches("Error: ").to_owned(),
+            ))),
+            Some(Ok(token)) => {
+                // the tokens are slices of the html template
+                // so the pointer difference is the position in the template.
+                // Only the EndElement of a self-closing element is a static empty str.
+                let slice = match &token {
+                    Token::StartElement(txt)
+                    | Token::EndElement(txt)
+                    | Token::Attribute(txt, _)
+                    | Token::TextNode(txt)
+                    | Token::Comment(txt) => txt,
+                };
+                let offset =
+                    (slice.as_ptr() as usize).wrapping_sub(self.html_template.as_ptr() as usize);
+                if offset <= self.html_template.len() {
+                    self.last_offset = offset;
+                }
+                Ok(Some(token))
+            }
+        }
+    }
+
+    /// error on the position of the last token
+    fn error(&self, kind: TemplateErrorKind) -> TemplateError {
+        TemplateError::new(
+            kind,
+            self.template_name,
+            self.html_template,
+            self.last_offset,
+            &self.dom_path,
+        )
+    }
+
+    /// the position of the last token
+    fn position(&self) -> TemplatePosition {
+        let (line, column) = line_and_column(self.html_template, self.last_offset);
+        TemplatePosition {
+            template_name: self.template_name.to_owned(),
+            line,
+            column,
+        }
+    }
+
+    /// Recursive function to parse the attributes and sub-nodes of one element.
+    fn parse_element(&mut self, name: &str) -> Result<TemplateElement, TemplateError> {
+        let mut element = TemplateElement {
+            name: name.to_owned(),
+            attributes: vec![],
+            children: vec![],
+            key_position: None,
+        };
+        loop {
+            let token = match self.next_token()? {
+                None => return Err(self.error(TemplateErrorKind::UnexpectedEndOfTemplate)),
+                Some(token) => token,
+            };
+            match token {
+                Token::StartElement(child_name) => {
+                    self.dom_path.push(child_name.to_owned());
+                    let child_element = self.parse_element(child_name)?;
+                    element.children.push(TemplateNode::Element(child_element));
+                }
+                Token::Attribute(attr_name, value) => {
+                    if attr_name.starts_with("data-wt-") {
+                        // the rest of the name does not matter,
+                        // but it should be nice to be te name of the next attribute.
+                        self.check_prefix(attr_name, value)?;
+                        element.attributes.push(TemplateAttribute::ReplaceString {
+                            name: attr_name.to_owned(),
+                            fn_name: value.to_owned(),
+                        });
+                    } else if attr_name.starts_with("data-wb-") {
+                        // the boolean is applied to the next attribute
+                        // for boolean html attributes like disabled, checked, hidden, selected
+                        self.check_prefix(attr_name, value)?;
+                        element.attributes.push(TemplateAttribute::RetainBoolean {
+                            name: attr_name.to_owned(),
+                            fn_name: value.to_owned(),
+                        });
+                    } else if let Some(class_name) = attr_name.strip_prefix("data-wc-") {
+                        // the designer's static classes stay in the template
+                        self.check_prefix(attr_name, value)?;
+                        element.attributes.push(TemplateAttribute::ToggleClass {
+                            class_name: class_name.to_owned(),
+                            fn_name: value.to_owned(),
+                        });
+                    } else if let Some(event_and_modifiers) = attr_name.strip_prefix("data-on-") {
+                        // it must look like data-on-click="wl_xxx" wl_ = webbrowser listener
+                        // with modifiers data-on-keyup.enter.prevent="wl_xxx"
+                        // and more listeners for the same event data-on-click="wl_xxx wl_yyy"
+                        let mut split = event_and_modifiers.split('.');
+                        let event = unwrap!(split.next());
+                        let mut modifiers = vec![];
+                        for modifier in split {
+                            match ListenerModifier::parse(modifier) {
+                                Some(listener_modifier) => modifiers.push(listener_modifier),
+                                None => {
+                                    return Err(self.error(
+                                        TemplateErrorKind::UnknownListenerModifier {
+                                            attribute: attr_name.to_owned(),
+                                            modifier: modifier.to_owned(),
+                                        },
+                                    ))
+                                }
+                            }
+                        }
+                        let listener_values: Vec<&str> = if value.is_empty() {
+                            vec![value]
+                        } else {
+                            split_listener_values(value)
+                        };
+                        for listener_value in listener_values {
+                            let (fn_name, args) = match split_listener_call(listener_value) {
+                                Some(fn_name_and_args) => fn_name_and_args,
+                                None => {
+                                    return Err(self.error(
+                                        TemplateErrorKind::BadListenerArguments {
+                                            attribute: attr_name.to_owned(),
+                                            value: listener_value.to_owned(),
+                                        },
+                                    ))
+                                }
+                            };
+                            if !fn_name.is_empty() {
+                                self.check_prefix(attr_name, fn_name)?;
+                            }
+                            let args = args.map(|args| {
+                                split_listener_args(args)
+                                    .into_iter()
+                                    .map(|arg| template_listener_arg(arg, self.entity_decoding))
+                                    .collect()
+                            });
+                            element.attributes.push(TemplateAttribute::Listener {
+                                event: event.to_owned(),
+                                fn_name: fn_name.to_owned(),
+                                modifiers: modifiers.clone(),
+                                args,
+                            });
+                        }
+                    } else {
+                        if attr_name == ATTR_KEY {
+                            element.key_position = Some(self.position());
+                        }
+                        match split_placeholders(value, self.entity_decoding) {
+                            Some(parts) => {
+                                element.attributes.push(TemplateAttribute::Interpolated {
+                                    name: attr_name.to_owned(),
+                                    parts,
+                                })
+                            }
+                            None => element.attributes.push(TemplateAttribute::Static {
+                                name: attr_name.to_owned(),
+                                value: decode_entities(value, self.entity_decoding),
+                            }),
+                        }
+                    }
+                }
+                Token::TextNode(txt) => match split_placeholders(txt, self.entity_decoding) {
+                    Some(parts) => element.children.push(TemplateNode::InterpolatedText(parts)),
+                    None => element.children.push(TemplateNode::Text(decode_entities(
+                        txt,
+                        self.entity_decoding,
+                    ))),
+                },
+                Token::Comment(txt) => {
+                    if txt == "end_of_wt" {
+                        // a special comment <!--end_of_wt--> just to end the wt_ replace string
+                        // if there are more replacing inside one text node
+                    } else if txt.starts_with("wt_") {
+                        element
+                            .children
+                            .push(TemplateNode::ReplaceString(txt.to_owned()));
+                    } else if txt.starts_with("wn_") {
+                        element
+                            .children
+                            .push(TemplateNode::ReplaceNodes(txt.to_owned()));
+                    } else if txt == "wb_else" {
+                        self.check_else(&element.children, txt)?;
+                        element.children.push(TemplateNode::Else);
+                    } else if let Some(else_if) = else_if_name(txt) {
+                        self.check_else(&element.children, txt)?;
+                        let fn_name = else_if.map_err(|kind| self.error(kind))?;
+                        element
+                            .children
+                            .push(TemplateNode::ElseIf(fn_name.to_owned()));
+                    } else if txt.starts_with("wb_") {
+                        element
+                            .children
+                            .push(TemplateNode::RetainBoolean(txt.to_owned()));
+                    } else if txt.starts_with("wr_") {
+                        element.children.push(TemplateNode::Repeat(txt.to_owned()));
+                    } else if let Some(include_name) = txt.strip_prefix("wi_") {
+                        // the include is resolved later with a TemplateSource
+                        element
+                            .children
+                            .push(TemplateNode::Include(include_name.trim().to_owned()));
+                    } else {
+                        // nothing. it is really a comment
+                    }
+                }
+                Token::EndElement(end_name) => {
+                    // it can be also auto-closing element
+                    let last_name = unwrap!(self.dom_path.last());
+                    if last_name == end_name || end_name.is_empty() {
+                        self.dom_path.pop();
+                        return Ok(element);
+                    } else {
+                        return Err(self.error(TemplateErrorKind::MismatchedEndTag {
+                            start: last_name.to_owned(),
+                            end: end_name.to_owned(),
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    /// the value of the special attribute must start with the prefix
+    fn check_prefix(&self, attr_name: &str, value: &str) -> Result<(), TemplateError> {
+        if value.starts_with(expected_prefix(attr_name)) {
+            Ok(())
+        } else {
+            Err(self.error(TemplateErrorKind::BadMarkerPrefix {
+                attribute: attr_name.to_owned(),
+                value: value.to_owned(),
+            }))
+        }
+    }
+
+    /// The else marker must come after an element with the wb_ or wb_else_if marker.
+    fn check_else(&self, children: &[TemplateNode], marker: &str) -> Result<(), TemplateError> {
+        let mut iter = children.iter().rev();
+        // the last element
+        loop {
+            match iter.next() {
+                Some(TemplateNode::Element(_)) | Some(TemplateNode::Include(_)) => break,
+                Some(TemplateNode::Text(_)) => {}
+                _ => return Err(self.error(TemplateErrorKind::ElseWithoutIf(marker.to_owned()))),
+            }
+        }
+        // the markers before the last element
+        for template_node in iter {
+            match template_node {
+                TemplateNode::RetainBoolean(_) | TemplateNode::ElseIf(_) => return Ok(()),
+                TemplateNode::ReplaceString(_)
+                | TemplateNode::ReplaceNodes(_)
+                | TemplateNode::Repeat(_) => {}
+                _ => break,
+            }
+        }
+        Err(self.error(TemplateErrorKind::ElseWithoutIf(marker.to_owned())))
+    }
+}
+
+/// The name in the marker <!--wb_else_if wb_xxx-->, None if the comment is not this marker.
+/// The name must come after a space and start with wb_,
+/// so <!--wb_else_if--> and <!--wb_else_ifwb_xxx--> are errors and not wb_ markers.
+pub(crate) fn else_if_name(txt: &str) -> Option<Result<&str, TemplateErrorKind>> {
+    let rest = txt.strip_prefix("wb_else_if")?;
+    let fn_name = rest.trim();
+    if !rest.starts_with(char::is_whitespace) {
+        return Some(Err(TemplateErrorKind::BadMarkerPrefix {
+            attribute: "wb_else_if".to_owned(),
+            value: txt.to_owned(),
+        }));
+    }
+    if !fn_name.starts_with("wb_") {
+        return Some(Err(TemplateErrorKind::BadMarkerPrefix {
+            attribute: "wb_else_if".to_owned(),
+            value: fn_name.to_owned(),
+        }));
+    }
+    // return
+    Some(Ok(fn_name))
+}
+
+/// Splits the value of data-on-xxx into listeners separated by whitespace.
+/// The whitespace inside the arguments wl_xxx(1, 'a b') does not split.
+pub(crate) fn split_listener_values(value: &str) -> Vec<&str> {
+    let mut listener_values = vec![];
+    let mut start: Option<usize> = None;
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (pos, ch) in value.char_indices() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => {}
+            None if ch == '\'' || ch == '"' => quote = Some(ch),
+            None if ch == '(' => depth += 1,
+            None if ch == ')' => depth -= 1,
+            None if ch.is_whitespace() && depth <= 0 => {
+                if let Some(start) = start.take() {
+                    listener_values.push(&value[start..pos]);
+                }
+                continue;
+            }
+            None => {}
+        }
+        if start.is_none() {
+            start = Some(pos);
+        }
+    }
+    if let Some(start) = start {
+        listener_values.push(&value[start..]);
+    }
+    // return
+    listener_values
+}
+
+/// The argument with placeholders is split into parts, without the quotes around it.
+fn template_listener_arg(arg: &str, entity_decoding: EntityDecoding) -> TemplateListenerArg {
+    let arg = arg.trim();
+    let unquoted = ['\'', '"'].iter().find_map(|quote| {
+        if arg.len() >= 2 && arg.starts_with(*quote) && arg.ends_with(*quote) {
+            Some(&arg[1..arg.len() - 1])
+        } else {
+            None
+        }
+    });
+    match split_placeholders(unquoted.unwrap_or(arg), entity_decoding) {
+        Some(parts) => TemplateListenerArg::Interpolated {
+            parts,
+            quoted: unquoted.is_some(),
+        },
+        None => TemplateListenerArg::Static(decode_entities(arg, entity_decoding)),
+    }
+}
+
+/// Splits wl_xxx(args) into the name and the arguments.
+/// Without parentheses the arguments are None. Returns None if the parentheses are not closed.
+pub(crate) fn split_listener_call(listener_value: &str) -> Option<(&str, Option<&str>)> {
+    match listener_value.find('(') {
+        None => Some((listener_value, None)),
+        Some(pos) => {
+            let args = listener_value[pos + 1..].strip_suffix(')')?;
+            Some((&listener_value[..pos], Some(args)))
+        }
+    }
+}
+
+/// Splits the text with inline placeholders {{wt_xxx}} into parts.
+/// Returns None if there is no placeholder, so the text stays static.
+/// Curly braces without wt_ are just text.
+/// The static parts are decoded as defined by entity_decoding.
+pub fn split_placeholders(txt: &str, entity_decoding: EntityDecoding) -> Option<Vec<TextPart>> {
+    if !txt.contains("{{") {
+        return None;
+    }
+    let mut parts = vec![];
+    let mut has_placeholder = false;
+    let mut static_text = String::new();
+    let mut rest = txt;
+    while let Some(start) = rest.find("{{") {
+        let after_start = &rest[start + 2..];
+        match after_start.find("}}") {
+            Some(end) if after_start[..end].trim().starts_with("wt_") => {
+                static_text.push_str(&rest[..start]);
+                if !static_text.is_empty() {
+                    parts.push(TextPart::Static(decode_entities(
+                        &static_text,
+                        entity_decoding,
+                    )));
+                    static_text.clear();
+                }
+                let fn_name = after_start[..end].trim();
+                parts.push(TextPart::ReplaceString(fn_name.to_owned()));
+                has_placeholder = true;
+                rest = &after_start[end + 2..];
+            }
+            _ => {
+                // not a placeholder, the braces are text
+                static_text.push_str(&rest[..start + 2]);
+                rest = after_start;
+            }
+        }
+    }
+    static_text.push_str(rest);
+    if !static_text.is_empty() {
+        parts.push(TextPart::Static(decode_entities(
+            &static_text,
+            entity_decoding,
+        )));
+    }
+    if has_placeholder {
+        Some(parts)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn else_if_needs_a_space_and_a_name() {
+        for txt in ["wb_else_if", "wb_else_ifwb_x", "wb_else_if wt_x"] {
+            let html_template = format!("<div><!--wb_a--><p>a</p><!--{}--><p>b</p></div>", txt);
+            let err = unwrap!(ParsedTemplate::parse(&html_template).err());
+            assert!(
+                matches!(err.kind, TemplateErrorKind::BadMarkerPrefix { .. }),
+                "{}",
+                txt
+            );
+        }
+        assert!(ParsedTemplate::parse(
+            "<div><!--wb_a--><p>a</p><!--wb_else_if wb_b--><p>b</p></div>"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn listener_modifier_parse() {
+        assert_eq!(
+            ListenerModifier::parse("prevent"),
+            Some(ListenerModifier::Prevent)
+        );
+        assert_eq!(
+            ListenerModifier::parse("self"),
+            Some(ListenerModifier::SelfOnly)
+        );
+        assert_eq!(
+            ListenerModifier::parse("esc"),
+            Some(ListenerModifier::Key("Escape".to_owned()))
+        );
+        assert_eq!(
+            ListenerModifier::parse("a"),
+            Some(ListenerModifier::Key("a".to_owned()))
+        );
+        assert_eq!(ListenerModifier::parse("unknown"), None);
+    }
+
+    #[test]
+    fn listener_modifier_parse_durations() {
+        assert_eq!(
+            ListenerModifier::parse("debounce-300ms"),
+            Some(ListenerModifier::Debounce(300))
+        );
+        assert_eq!(
+            ListenerModifier::parse("throttle-2s"),
+            Some(ListenerModifier::Throttle(2000))
+        );
+        assert_eq!(ListenerModifier::parse("debounce-300"), None);
+        assert_eq!(ListenerModifier::parse("throttle-xms"), None);
+    }
+}
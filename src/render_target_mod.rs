@@ -0,0 +1,617 @@
+//! **render_target_mod**
+//! The walker through the parsed template is the same for every backend.
+//! It evaluates the markers and drives the RenderTarget: begin element, attributes,
+//! listeners, text, the nodes from <!--wn_xxx--> and end element.
+//! The listeners and <!--wn_xxx--> come to the backend as markers. The marker chooses
+//! the plain or the `_in_repeat` method, so the backend does not call the rrc itself.
+//! The dodrio backend builds the dodrio Node with ElementBuilder.
+//! The HtmlNodeTarget in server_render_mod builds the tree for the html string.
+//! A new backend (a test backend or a different vdom) implements only RenderTarget.
+
+// region: use
+use crate::event_listener_mod::*;
+use crate::html_entity_mod::decode_entities;
+use crate::html_template_mod::*;
+use crate::parsed_template_mod::*;
+use crate::template_error_mod::*;
+use dodrio::{
+    builder::{text, ElementBuilder},
+    bumpalo, Attribute, Listener, Node, RenderContext,
+};
+use unwrap::unwrap;
+// endregion: use
+
+/// The backend that receives the rendered elements from the walker.
+/// The elements are nested: begin_element() opens the element and end_element() closes it.
+/// The attributes, listeners and children go to the last opened element.
+pub trait RenderTarget {
+    /// the rendered root node of this backend
+    type Node;
+    /// opens the element with the namespace of this element
+    fn begin_element(&mut self, name: &str, html_or_svg: HtmlOrSvg);
+    /// the attribute name is already mapped for the namespace
+    fn attribute(&mut self, name: &str, value: &str);
+    /// the value of data-key, the key for the diff
+    fn key(&mut self, _key: &str) {}
+    /// the wb_ marker that retained this element, after the attributes
+    fn retained_by(&mut self, _marker: &str) {}
+    /// one call per event with all its data-on- listeners in the template order
+    fn listener<R: HtmlTemplating + ?Sized>(&mut self, event: &str, markers: &[ListenerMarker<R>]);
+    /// the text node, entities already decoded, not escaped
+    fn text(&mut self, txt: &str);
+    /// adds the children for the marker <!--wn_xxx-->, the marker calls the rrc
+    fn splice_children<R: HtmlTemplating + ?Sized>(&mut self, marker: &SpliceMarker<R>);
+    /// closes the last opened element
+    fn end_element(&mut self);
+    /// the root node after the last end_element()
+    fn finish(self) -> Option<Self::Node>;
+}
+
+/// Renders the parsed template to the backend and returns the root node.
+/// The layout and the includes must be resolved in advance.
+pub fn render_to_target<R: HtmlTemplating + ?Sized, T: RenderTarget>(
+    rrc: &R,
+    parsed_template: &ParsedTemplate,
+    html_or_svg_parent: HtmlOrSvg,
+    mut target: T,
+) -> Result<T::Node, TemplateError> {
+    render_element(
+        rrc,
+        &parsed_template.root,
+        &parsed_template.root.name,
+        None,
+        html_or_svg_parent,
+        None,
+        &mut Vec::new(),
+        &mut target,
+    )?;
+    // return
+    Ok(unwrap!(target.finish()))
+}
+
+/// Recursive function that renders the element with attributes, listeners and children.
+/// The markers are remembered and applied to the next node.
+/// The marker is the wb_ marker that retained this element.
+/// The element_path is the root name and the child indexes in the template, like html/1/0.
+/// The repeat_index is the index of the item, if this element is repeated by wr_.
+/// Returns the value of data-key for the check among the siblings.
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+fn render_element<'t, R: HtmlTemplating + ?Sized, T: RenderTarget>(
+    rrc: &R,
+    template_element: &'t TemplateElement,
+    element_path: &str,
+    repeat_index: Option<usize>,
+    html_or_svg_parent: HtmlOrSvg,
+    marker: Option<&str>,
+    repeat: &mut Vec<RepeatItem<'t>>,
+    target: &mut T,
+) -> Result<Option<String>, TemplateError> {
+    // the element inherits html_or_svg from the parent, but cannot change the parent
+    // the tagname svg or math changes the namespace for this element
+    let html_or_svg_element = html_or_svg_parent.for_element(&template_element.name);
+    // foreignObject changes to html for children, not for this element
+    let html_or_svg_children = html_or_svg_element.for_children(&template_element.name);
+    target.begin_element(&template_element.name, html_or_svg_element);
+
+    // region: attributes
+    // the classes from data-wc- are added to the class attribute
+    let mut toggle_classes: Vec<&str> = vec![];
+    for attribute in &template_element.attributes {
+        if let TemplateAttribute::ToggleClass {
+            class_name,
+            fn_name,
+        } = attribute
+        {
+            if call_retain_next_node(rrc, fn_name, repeat) {
+                toggle_classes.push(class_name);
+            }
+        }
+    }
+    let mut class_is_rendered = false;
+    let mut key: Option<String> = None;
+    let mut replace_string: Option<&str> = None;
+    let mut replace_boolean_attribute: Option<&str> = None;
+    // the listeners need the data-key, so they are created after the loop
+    let mut listener_attributes: Vec<&TemplateAttribute> = vec![];
+    for attribute in &template_element.attributes {
+        match attribute {
+            TemplateAttribute::ToggleClass { .. } => {
+                // already evaluated before the class attribute
+            }
+            TemplateAttribute::ReplaceString { fn_name, .. } => {
+                // the rest of the name does not matter,
+                // but it should be nice to be te name of the next attribute.
+                // The replace_string will always be applied to the next attribute.
+                replace_string = Some(fn_name);
+            }
+            TemplateAttribute::Listener { .. } => {
+                // it must look like data-on-click="wl_xxx" wl_ = webbrowser listener
+                listener_attributes.push(attribute);
+            }
+            TemplateAttribute::RetainBoolean { fn_name, .. } => {
+                // the rest of the name does not matter,
+                // but it should be nice to be te name of the next attribute.
+                replace_boolean_attribute = Some(fn_name);
+            }
+            TemplateAttribute::Static { name, .. }
+            | TemplateAttribute::Interpolated { name, .. } => {
+                // empty the replace_string for the next attribute
+                let replace_string = replace_string.take();
+                // if the boolean is empty or true then render the next attribute
+                if let Some(fn_name) = replace_boolean_attribute.take() {
+                    if !call_retain_next_node(rrc, fn_name, repeat) {
+                        continue;
+                    }
+                }
+                // the xmlns declarations are not rendered
+                let name = match html_or_svg_element.attribute_name(name) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let value = attribute_value(rrc, attribute, replace_string, repeat);
+                if name == ATTR_KEY {
+                    // data-key is the key for the diff, not an attribute
+                    target.key(&value);
+                    key = Some(value);
+                } else if name == "class" && !toggle_classes.is_empty() {
+                    class_is_rendered = true;
+                    target.attribute(name, &add_classes(&value, &toggle_classes));
+                } else {
+                    target.attribute(name, &value);
+                }
+            }
+        }
+    }
+    if !class_is_rendered && !toggle_classes.is_empty() {
+        // the element has no class attribute in the template
+        target.attribute("class", &add_classes("", &toggle_classes));
+    }
+    if let Some(marker) = marker {
+        target.retained_by(marker);
+    }
+    // the state of the modifiers follows the data-key, without it the index of the repeat item
+    let element_path = match (&key, repeat_index) {
+        (Some(key), _) => format!("{}#{}", element_path, key),
+        (None, Some(index)) => format!("{}[{}]", element_path, index),
+        (None, None) => element_path.to_owned(),
+    };
+    // the listeners for the same event go to the target together
+    let mut listeners: Vec<(&str, Vec<ListenerMarker<R>>)> = vec![];
+    for attribute in listener_attributes {
+        if let TemplateAttribute::Listener {
+            event,
+            fn_name,
+            modifiers,
+            args,
+        } = attribute
+        {
+            let listener = ListenerMarker {
+                rrc,
+                event,
+                fn_name,
+                args: args.as_ref().map(|args| listener_args(rrc, args, repeat)),
+                modifiers,
+                state_key: listener_state_key(&element_path, event, fn_name),
+                repeat,
+            };
+            match listeners.iter_mut().find(|(x, _)| x == event) {
+                Some((_, event_listeners)) => event_listeners.push(listener),
+                None => listeners.push((event, vec![listener])),
+            }
+        }
+    }
+    for (event, event_listeners) in &listeners {
+        target.listener(event, event_listeners);
+    }
+    // endregion: attributes
+
+    // region: children
+    // the replace_string is shared with the attributes:
+    // a data-wt- after the last attribute replaces the first text node
+    let mut replace_vec_nodes: Option<&str> = None;
+    // the marker wb_, wb_else_if or wb_else for the next node
+    let mut replace_boolean: Option<&'t TemplateNode> = None;
+    // the result of the last wb_ and wb_else_if conditions for wb_else
+    let mut else_chain: Option<bool> = None;
+    let mut replace_repeat: Option<&'t str> = None;
+    // the keys of the rendered sibling elements must be unique
+    let mut sibling_keys = SiblingKeys::default();
+    for (child_index, template_node) in template_element.children.iter().enumerate() {
+        match template_node {
+            TemplateNode::Element(template_child) => {
+                // if the boolean is empty or true then render the next node
+                let (retain, marker) = match replace_boolean.take() {
+                    Some(TemplateNode::RetainBoolean(fn_name)) => {
+                        let retain = call_retain_next_node(rrc, fn_name, repeat);
+                        else_chain = Some(retain);
+                        (retain, Some(fn_name.as_str()))
+                    }
+                    Some(TemplateNode::ElseIf(fn_name)) => {
+                        // the condition is not called if a previous condition was true
+                        if else_chain == Some(true) {
+                            (false, None)
+                        } else {
+                            let retain = call_retain_next_node(rrc, fn_name, repeat);
+                            else_chain = Some(retain);
+                            (retain, Some(fn_name.as_str()))
+                        }
+                    }
+                    Some(_) => {
+                        // wb_else ends the chain
+                        let retain = else_chain != Some(true);
+                        else_chain = None;
+                        (retain, Some("wb_else"))
+                    }
+                    None => {
+                        else_chain = None;
+                        (true, None)
+                    }
+                };
+                let repl_nodes = replace_vec_nodes.take();
+                let repeat_fn_name = replace_repeat.take();
+                if !retain {
+                    continue;
+                }
+                // without the repeat marker the node is rendered only once
+                let count = match repeat_fn_name {
+                    Some(fn_name) => rrc.repeat_count(fn_name, repeat),
+                    None => 1,
+                };
+                for index in 0..count {
+                    if let Some(fn_name) = repeat_fn_name {
+                        repeat.push(RepeatItem { fn_name, index });
+                    }
+                    if let Some(fn_name) = repl_nodes {
+                        target.splice_children(&SpliceMarker {
+                            rrc,
+                            fn_name,
+                            repeat,
+                        });
+                    } else {
+                        let child_key = render_element(
+                            rrc,
+                            template_child,
+                            &format!("{}/{}", element_path, child_index),
+                            repeat_fn_name.map(|_| index),
+                            html_or_svg_children,
+                            marker,
+                            repeat,
+                            target,
+                        )?;
+                        if let Some(child_key) = child_key {
+                            sibling_keys.insert(
+                                &child_key,
+                                &template_element.name,
+                                &template_child.name,
+                                template_child.key_position.as_ref(),
+                            )?;
+                        }
+                    }
+                    if repeat_fn_name.is_some() {
+                        repeat.pop();
+                    }
+                }
+            }
+            TemplateNode::Text(_) | TemplateNode::InterpolatedText(_) => {
+                let txt = if let Some(fn_name) = replace_string.take() {
+                    // empty the replace_string for the next node
+                    let repl = call_replace_with_string(rrc, fn_name, repeat);
+                    decode_entities(&repl, rrc.entity_decoding())
+                } else if let TemplateNode::InterpolatedText(parts) = template_node {
+                    replace_text_parts(rrc, parts, repeat)
+                } else if let TemplateNode::Text(txt) = template_node {
+                    txt.to_owned()
+                } else {
+                    String::new()
+                };
+                target.text(&txt);
+            }
+            TemplateNode::ReplaceString(fn_name) => {
+                // it must look like <!--wt_get_text-->  wt_ = webbrowser text
+                replace_string = Some(fn_name);
+            }
+            TemplateNode::ReplaceNodes(fn_name) => {
+                // it must look like <!--wn_get_nodes-->  wn_ = webbrowser nodes
+                replace_vec_nodes = Some(fn_name);
+            }
+            TemplateNode::RetainBoolean(_) | TemplateNode::ElseIf(_) | TemplateNode::Else => {
+                // it must look like <!--wb_get_bool-->  wb_ = webbrowser boolean
+                // boolean if this is true than render the next node, else don't render
+                // <!--wb_else_if wb_get_bool--> and <!--wb_else--> continue the chain
+                replace_boolean = Some(template_node);
+            }
+            TemplateNode::Repeat(fn_name) => {
+                // it must look like <!--wr_get_items-->  wr_ = webbrowser repeat
+                // the next node is rendered once per item
+                replace_repeat = Some(fn_name);
+            }
+            TemplateNode::Include(include_name) => {
+                // it must look like <!--wi_header-->  wi_ = webbrowser include
+                // resolve_includes() replaces it before rendering
+                return Err(TemplateError {
+                    kind: TemplateErrorKind::UnknownInclude {
+                        include: include_name.to_owned(),
+                        chain: String::new(),
+                    },
+                    template_name: String::new(),
+                    line: 0,
+                    column: 0,
+                    dom_path: vec![template_element.name.clone()],
+                });
+            }
+        }
+    }
+    // endregion: children
+
+    target.end_element();
+    // return
+    Ok(key)
+}
+
+// region: markers for the backends
+/// The data-on- listener found by the walker, without the closure.
+/// The backend reads the fields or makes the dodrio listener with listener_box().
+pub struct ListenerMarker<'r, R: ?Sized> {
+    rrc: &'r R,
+    /// the event name like click
+    pub event: &'r str,
+    /// the fn_name wl_xxx
+    pub fn_name: &'r str,
+    /// the arguments with the placeholders already replaced
+    pub args: Option<Vec<ListenerArg>>,
+    pub modifiers: &'r [ListenerModifier],
+    /// the key of the modifier state from listener_state_key(), for the backends
+    /// that create the elements again on every render
+    pub state_key: String,
+    repeat: &'r [RepeatItem<'r>],
+}
+
+impl<'r, R: HtmlTemplating + ?Sized> ListenerMarker<'r, R> {
+    /// the repeat items around the element
+    pub fn repeat(&self) -> &[RepeatItem<'r>] {
+        self.repeat
+    }
+
+    /// set_event_listener(), the `_in_repeat` or the `_with_args` method, wrapped with the modifiers
+    pub fn listener_box(&self) -> ListenerBox {
+        let listener = if let Some(args) = &self.args {
+            self.rrc.set_event_listener_with_args(
+                self.fn_name.to_string(),
+                args.clone(),
+                self.repeat,
+            )
+        } else if self.repeat.is_empty() {
+            self.rrc.set_event_listener(self.fn_name.to_string())
+        } else {
+            self.rrc
+                .set_event_listener_in_repeat(self.fn_name.to_string(), self.repeat)
+        };
+        // return
+        with_modifiers(listener, self.modifiers.to_vec(), self.event, self.fn_name)
+    }
+}
+
+/// The marker <!--wn_xxx--> found by the walker.
+/// The backend asks for the nodes or for the html.
+pub struct SpliceMarker<'r, R: ?Sized> {
+    rrc: &'r R,
+    /// the fn_name wn_xxx
+    pub fn_name: &'r str,
+    repeat: &'r [RepeatItem<'r>],
+}
+
+impl<'r, R: HtmlTemplating + ?Sized> SpliceMarker<'r, R> {
+    /// the repeat items around the marker
+    pub fn repeat(&self) -> &[RepeatItem<'r>] {
+        self.repeat
+    }
+
+    /// replace_with_nodes() or replace_with_nodes_in_repeat()
+    pub fn nodes<'a>(&self, cx: &mut RenderContext<'a>) -> Vec<Node<'a>> {
+        if self.repeat.is_empty() {
+            self.rrc.replace_with_nodes(cx, self.fn_name)
+        } else {
+            self.rrc
+                .replace_with_nodes_in_repeat(cx, self.fn_name, self.repeat)
+        }
+    }
+
+    /// replace_with_html() or replace_with_html_in_repeat(), for the backends without RenderContext
+    pub fn html(&self) -> String {
+        if self.repeat.is_empty() {
+            self.rrc.replace_with_html(self.fn_name)
+        } else {
+            self.rrc
+                .replace_with_html_in_repeat(self.fn_name, self.repeat)
+        }
+    }
+}
+// endregion: markers for the backends
+
+// region: marker calls for the walker
+/// replace_with_string() outside of repeat or replace_with_string_in_repeat() inside repeat
+pub(crate) fn call_replace_with_string<R: HtmlTemplating + ?Sized>(
+    rrc: &R,
+    fn_name: &str,
+    repeat: &[RepeatItem],
+) -> String {
+    if repeat.is_empty() {
+        rrc.replace_with_string(fn_name)
+    } else {
+        rrc.replace_with_string_in_repeat(fn_name, repeat)
+    }
+}
+
+/// The value of the static or interpolated attribute
+/// or the replaced string from the data-wt- before it.
+pub(crate) fn attribute_value<R: HtmlTemplating + ?Sized>(
+    rrc: &R,
+    attribute: &TemplateAttribute,
+    replace_string: Option<&str>,
+    repeat: &[RepeatItem],
+) -> String {
+    if let Some(fn_name) = replace_string {
+        let repl = call_replace_with_string(rrc, fn_name, repeat);
+        decode_entities(&repl, rrc.entity_decoding())
+    } else if let TemplateAttribute::Interpolated { parts, .. } = attribute {
+        replace_text_parts(rrc, parts, repeat)
+    } else if let TemplateAttribute::Static { value, .. } = attribute {
+        value.to_owned()
+    } else {
+        String::new()
+    }
+}
+
+/// joins the static parts and the replaced placeholders {{wt_xxx}}
+pub(crate) fn replace_text_parts<R: HtmlTemplating + ?Sized>(
+    rrc: &R,
+    parts: &[TextPart],
+    repeat: &[RepeatItem],
+) -> String {
+    let mut txt = String::new();
+    for part in parts {
+        match part {
+            TextPart::Static(static_text) => txt.push_str(static_text),
+            TextPart::ReplaceString(fn_name) => {
+                let repl = call_replace_with_string(rrc, fn_name, repeat);
+                txt.push_str(&decode_entities(&repl, rrc.entity_decoding()));
+            }
+        }
+    }
+    // return
+    txt
+}
+
+/// replaces the placeholders inside every argument and parses it
+pub(crate) fn listener_args<R: HtmlTemplating + ?Sized>(
+    rrc: &R,
+    args: &[TemplateListenerArg],
+    repeat: &[RepeatItem],
+) -> Vec<ListenerArg> {
+    args.iter()
+        .map(|arg| match arg {
+            TemplateListenerArg::Static(value) => ListenerArg::parse(value),
+            TemplateListenerArg::Interpolated { parts, quoted } => {
+                ListenerArg::parse_replaced(&replace_text_parts(rrc, parts, repeat), *quoted)
+            }
+        })
+        .collect()
+}
+
+/// retain_next_node_or_attribute() outside of repeat or the `_in_repeat` method inside repeat
+pub(crate) fn call_retain_next_node<R: HtmlTemplating + ?Sized>(
+    rrc: &R,
+    fn_name: &str,
+    repeat: &[RepeatItem],
+) -> bool {
+    if repeat.is_empty() {
+        rrc.retain_next_node_or_attribute(fn_name)
+    } else {
+        rrc.retain_next_node_or_attribute_in_repeat(fn_name, repeat)
+    }
+}
+// endregion: marker calls for the walker
+
+/// The ElementBuilder with the bumpalo vectors, as ElementBuilder::new() returns it.
+type DodrioElementBuilder<'a> = ElementBuilder<
+    'a,
+    bumpalo::collections::Vec<'a, Listener<'a>>,
+    bumpalo::collections::Vec<'a, Attribute<'a>>,
+    bumpalo::collections::Vec<'a, Node<'a>>,
+>;
+
+/// The dodrio backend. The strings are copied into the bump.
+/// I must `move` ElementBuilder because its methods are all `move`,
+/// so the opened elements are popped and pushed back on every call.
+pub struct DodrioRenderTarget<'a, 'c> {
+    cx: &'c mut RenderContext<'a>,
+    // the keys of the children are unique for every opened element
+    elements: Vec<(DodrioElementBuilder<'a>, SiblingKeys)>,
+    root: Option<Node<'a>>,
+}
+
+impl<'a, 'c> DodrioRenderTarget<'a, 'c> {
+    /// the backend for one render
+    pub fn new(cx: &'c mut RenderContext<'a>) -> Self {
+        DodrioRenderTarget {
+            cx,
+            elements: vec![],
+            root: None,
+        }
+    }
+
+    /// moves the last opened element through the builder method
+    fn update(&mut self, f: impl FnOnce(DodrioElementBuilder<'a>) -> DodrioElementBuilder<'a>) {
+        let (element, sibling_keys) = unwrap!(self.elements.pop());
+        self.elements.push((f(element), sibling_keys));
+    }
+
+    /// the node goes to the last opened element or becomes the root
+    fn push_node(&mut self, node: Node<'a>) {
+        if self.elements.is_empty() {
+            self.root = Some(node);
+        } else {
+            self.update(|element| element.child(node));
+        }
+    }
+}
+
+impl<'a, 'c> RenderTarget for DodrioRenderTarget<'a, 'c> {
+    type Node = Node<'a>;
+
+    fn begin_element(&mut self, name: &str, html_or_svg: HtmlOrSvg) {
+        let bump = self.cx.bump;
+        let mut element = ElementBuilder::new(bump, bump_str(bump, name));
+        if let Some(namespace) = html_or_svg.namespace_uri() {
+            element = element.namespace(Some(namespace));
+        }
+        self.elements.push((element, SiblingKeys::default()));
+    }
+
+    fn attribute(&mut self, name: &str, value: &str) {
+        let bump = self.cx.bump;
+        let name = bump_str(bump, name);
+        let value = bump_str(bump, value);
+        self.update(|element| element.attr(name, value));
+    }
+
+    fn key(&mut self, key: &str) {
+        // the key is unique among the children of the parent
+        let count = self.elements.len();
+        let key = if count >= 2 {
+            self.elements[count - 2].1.unique_node_key(key)
+        } else {
+            node_key(key)
+        };
+        self.update(|element| element.key(key));
+    }
+
+    fn listener<R: HtmlTemplating + ?Sized>(&mut self, event: &str, markers: &[ListenerMarker<R>]) {
+        // dodrio has only one listener per event on the element
+        let listener =
+            combine_listeners(markers.iter().map(ListenerMarker::listener_box).collect());
+        let event = bump_str(self.cx.bump, event);
+        self.update(|element| element.on(event, listener));
+    }
+
+    fn text(&mut self, txt: &str) {
+        // the walker already decoded the entities as defined by rrc.entity_decoding()
+        let node = text(bump_str(self.cx.bump, txt));
+        self.push_node(node);
+    }
+
+    fn splice_children<R: HtmlTemplating + ?Sized>(&mut self, marker: &SpliceMarker<R>) {
+        for repl_node in marker.nodes(self.cx) {
+            self.push_node(repl_node);
+        }
+    }
+
+    fn end_element(&mut self) {
+        let node = unwrap!(self.elements.pop()).0.finish();
+        self.push_node(node);
+    }
+
+    fn finish(self) -> Option<Node<'a>> {
+        self.root
+    }
+}
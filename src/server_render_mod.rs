@@ -0,0 +1,264 @@
+//! **server_render_mod**
+//! Renders the template to an html string, natively without dodrio RenderContext and without wasm.
+//! The pages can be pre-rendered on the server for the first paint or for the crawlers.
+//! The markers are evaluated by the same walker as for dodrio in render_target_mod,
+//! but the listeners are dropped and <!--wn_xxx--> calls replace_with_html().
+//! The template is first rendered to a tree of HtmlNode, then written as html.
+//! The hydration compares the same tree with the DOM in the browser.
+
+// region: use
+use crate::html_template_mod::*;
+use crate::parsed_template_mod::*;
+use crate::render_target_mod::*;
+use crate::template_error_mod::*;
+// endregion: use
+
+/// html elements without the end tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// the text inside these html elements is not escaped, only their end tag with escape_raw_text()
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// The attribute with the marker name on the elements retained by wb_ markers.
+/// Only in the html rendered for hydration.
+pub const ATTR_HYDRATION_MARKER: &str = "data-wm";
+
+/// The rendered node without dodrio.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HtmlNode {
+    /// element with attributes and children
+    Element {
+        name: String,
+        html_or_svg: HtmlOrSvg,
+        attributes: Vec<(String, String)>,
+        children: Vec<HtmlNode>,
+    },
+    /// text, not escaped yet
+    Text(String),
+    /// the html from replace_with_html() for the marker <!--wn_xxx-->
+    Html { fn_name: String, html: String },
+}
+
+impl HtmlNode {
+    /// Writes the escaped html.
+    /// For hydration the html from <!--wn_xxx--> is between the comments
+    /// <!--wn_xxx--> and <!--/wn_xxx-->.
+    pub fn write_html(&self, html: &mut String, for_hydration: bool) {
+        match self {
+            HtmlNode::Element {
+                name,
+                html_or_svg,
+                attributes,
+                children,
+            } => {
+                html.push('<');
+                html.push_str(name);
+                for (name, value) in attributes {
+                    html.push(' ');
+                    html.push_str(name);
+                    html.push_str("=\"");
+                    html.push_str(&escape_attribute(value));
+                    html.push('"');
+                }
+                html.push('>');
+                let is_html = *html_or_svg == HtmlOrSvg::Html;
+                if is_html && VOID_ELEMENTS.contains(&name.as_str()) {
+                    return;
+                }
+                for child in children {
+                    match child {
+                        HtmlNode::Text(txt)
+                            if is_html && RAW_TEXT_ELEMENTS.contains(&name.as_str()) =>
+                        {
+                            html.push_str(&escape_raw_text(txt))
+                        }
+                        _ => child.write_html(html, for_hydration),
+                    }
+                }
+                html.push_str("</");
+                html.push_str(name);
+                html.push('>');
+            }
+            HtmlNode::Text(txt) => html.push_str(&escape_text(txt)),
+            HtmlNode::Html {
+                fn_name,
+                html: replaced_html,
+            } => {
+                if for_hydration {
+                    html.push_str(&format!(
+                        "<!--{}-->{}<!--/{}-->",
+                        fn_name, replaced_html, fn_name
+                    ));
+                } else {
+                    html.push_str(replaced_html);
+                }
+            }
+        }
+    }
+}
+
+/// Renders the parsed template to the html string.
+/// The layout and the includes must be resolved in advance like for render_parsed().
+pub fn render_to_string<T: HtmlTemplating + ?Sized>(
+    rrc: &T,
+    parsed_template: &ParsedTemplate,
+    html_or_svg_parent: HtmlOrSvg,
+) -> Result<String, TemplateError> {
+    let mut html = String::new();
+    render_to_html_node(rrc, parsed_template, html_or_svg_parent, false)?
+        .write_html(&mut html, false);
+    // return
+    Ok(html)
+}
+
+/// Renders the parsed template to the tree of HtmlNode.
+/// For hydration the elements retained by wb_ markers get the attribute data-wm="wb_xxx".
+pub fn render_to_html_node<T: HtmlTemplating + ?Sized>(
+    rrc: &T,
+    parsed_template: &ParsedTemplate,
+    html_or_svg_parent: HtmlOrSvg,
+    for_hydration: bool,
+) -> Result<HtmlNode, TemplateError> {
+    render_to_target(
+        rrc,
+        parsed_template,
+        html_or_svg_parent,
+        HtmlNodeTarget::new(for_hydration),
+    )
+}
+
+/// escapes the text node: & < >
+pub fn escape_text(txt: &str) -> String {
+    txt.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes </ in the text of script and style as <\/.
+/// The text from wt_ markers could close the element with </script>.
+/// Only </script and </style in any case are escaped, the other </ stay as in the template.
+/// In javascript strings and in css <\/ is the same as </.
+pub fn escape_raw_text(txt: &str) -> String {
+    let mut escaped = String::with_capacity(txt.len());
+    let mut rest = txt;
+    while let Some(pos) = rest.find("</") {
+        escaped.push_str(&rest[..pos]);
+        rest = &rest[pos + 2..];
+        let closes_element = RAW_TEXT_ELEMENTS.iter().any(|name| {
+            rest.get(..name.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(name))
+        });
+        escaped.push_str(if closes_element { "<\\/" } else { "</" });
+    }
+    escaped.push_str(rest);
+    // return
+    escaped
+}
+
+/// escapes the attribute value inside double quotes: & < > "
+pub fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
+
+/// The backend for the tree of HtmlNode. The listeners are dropped.
+pub struct HtmlNodeTarget {
+    for_hydration: bool,
+    elements: Vec<HtmlNode>,
+    root: Option<HtmlNode>,
+}
+
+impl HtmlNodeTarget {
+    /// for_hydration adds the attribute data-wm to the elements retained by wb_ markers
+    pub fn new(for_hydration: bool) -> Self {
+        HtmlNodeTarget {
+            for_hydration,
+            elements: vec![],
+            root: None,
+        }
+    }
+
+    /// the node goes to the last opened element or becomes the root
+    fn push_node(&mut self, node: HtmlNode) {
+        match self.elements.last_mut() {
+            Some(HtmlNode::Element { children, .. }) => children.push(node),
+            _ => self.root = Some(node),
+        }
+    }
+
+    /// adds the attribute to the last opened element
+    fn push_attribute(&mut self, name: &str, value: &str) {
+        if let Some(HtmlNode::Element { attributes, .. }) = self.elements.last_mut() {
+            attributes.push((name.to_owned(), value.to_owned()));
+        }
+    }
+}
+
+impl RenderTarget for HtmlNodeTarget {
+    type Node = HtmlNode;
+
+    fn begin_element(&mut self, name: &str, html_or_svg: HtmlOrSvg) {
+        self.elements.push(HtmlNode::Element {
+            name: name.to_owned(),
+            html_or_svg,
+            attributes: vec![],
+            children: vec![],
+        });
+    }
+
+    fn attribute(&mut self, name: &str, value: &str) {
+        self.push_attribute(name, value);
+    }
+
+    fn retained_by(&mut self, marker: &str) {
+        if self.for_hydration {
+            self.push_attribute(ATTR_HYDRATION_MARKER, marker);
+        }
+    }
+
+    fn listener<R: HtmlTemplating + ?Sized>(
+        &mut self,
+        _event: &str,
+        _markers: &[ListenerMarker<R>],
+    ) {
+        // the listeners are set only in the browser
+    }
+
+    fn text(&mut self, txt: &str) {
+        self.push_node(HtmlNode::Text(txt.to_owned()));
+    }
+
+    fn splice_children<R: HtmlTemplating + ?Sized>(&mut self, marker: &SpliceMarker<R>) {
+        // the html from the project is not escaped
+        self.push_node(HtmlNode::Html {
+            fn_name: marker.fn_name.to_owned(),
+            html: marker.html(),
+        });
+    }
+
+    fn end_element(&mut self) {
+        if let Some(node) = self.elements.pop() {
+            self.push_node(node);
+        }
+    }
+
+    fn finish(self) -> Option<HtmlNode> {
+        self.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_text_escapes_only_the_end_tags() {
+        assert_eq!(
+            escape_raw_text(r#"if (a</b) { x = "</div>"; y = "</SCRIPT >"; }"#),
+            r#"if (a</b) { x = "</div>"; y = "<\/SCRIPT >"; }"#
+        );
+        assert_eq!(escape_raw_text("a</Style>b</"), "a<\\/Style>b</");
+    }
+}
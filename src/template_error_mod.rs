@@ -0,0 +1,249 @@
+//! **template_error_mod**
+//! Errors of html templating with the position where the template is broken.
+//! The designers edit the html templates live, so the error must tell
+//! the template name, line, column and the path of elements to the failure point.
+
+/// What went wrong in the html template.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemplateErrorKind {
+    /// the template does not start with an element
+    NoRootElement,
+    /// the end tag has a different name than the start tag
+    MismatchedEndTag { start: String, end: String },
+    /// the value of a special attribute does not start with the expected prefix
+    BadMarkerPrefix { attribute: String, value: String },
+    /// the modifier in data-on-xxx.modifier is not recognized
+    UnknownListenerModifier { attribute: String, modifier: String },
+    /// the arguments in data-on-xxx="wl_xxx(args)" have no closing parenthesis
+    BadListenerArguments { attribute: String, value: String },
+    /// the data-key is used twice among the sibling elements
+    DuplicateKey(String),
+    /// the else marker is not after an element with the wb_ marker
+    ElseWithoutIf(String),
+    /// the template ends before the element is closed
+    UnexpectedEndOfTemplate,
+    /// the sub_template <template name="xxx"> must have exactly one root element
+    SubTemplateRoot(String),
+    /// two sub_templates with the same name
+    DuplicateSubTemplate(String),
+    /// no sub_template with this name in the TemplateLibrary
+    UnknownSubTemplate(String),
+    /// the TemplateSource has no template for the include marker <!--wi_xxx-->
+    UnknownInclude { include: String, chain: String },
+    /// the template includes itself, directly or through other templates
+    IncludeCycle(String),
+    /// the TemplateSource has no template for data-layout="xxx"
+    UnknownLayout { layout: String, chain: String },
+    /// the layout has itself as layout, directly or through other layouts
+    LayoutCycle(String),
+    /// the page has a block that does not exist in the layout
+    UnknownBlock { block: String, layout: String },
+    /// error from the microxml parser
+    ParserError(String),
+}
+
+/// Error in the html template with the position of the failure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateError {
+    /// what went wrong
+    pub kind: TemplateErrorKind,
+    /// name of the template, can be empty
+    pub template_name: String,
+    /// line in the template, starts with 1, 0 if there is no position
+    pub line: usize,
+    /// column in the line, starts with 1, 0 if there is no position
+    pub column: usize,
+    /// names of the open elements at the failure point, from the root element
+    pub dom_path: Vec<String>,
+}
+
+/// The position of a node in the template, kept after parsing for the errors in render time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TemplatePosition {
+    /// name of the template, can be empty
+    pub template_name: String,
+    /// line in the template, starts with 1
+    pub line: usize,
+    /// column in the line, starts with 1
+    pub column: usize,
+}
+
+impl TemplateError {
+    /// Constructor with the position as byte offset in the template.
+    /// The offset is converted to line and column.
+    pub fn new(
+        kind: TemplateErrorKind,
+        template_name: &str,
+        html_template: &str,
+        offset: usize,
+        dom_path: &[String],
+    ) -> TemplateError {
+        let (line, column) = line_and_column(html_template, offset);
+        TemplateError {
+            kind,
+            template_name: template_name.to_owned(),
+            line,
+            column,
+            dom_path: dom_path.to_vec(),
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateErrorKind::NoRootElement => write!(f, "no root element"),
+            TemplateErrorKind::MismatchedEndTag { start, end } => write!(
+                f,
+                "End element not correct: starts <{}> ends </{}>",
+                start, end
+            ),
+            TemplateErrorKind::BadMarkerPrefix { attribute, value } if attribute == "wb_else_if" => {
+                write!(
+                    f,
+                    "wb_else_if needs a space and a name that starts with wb_ like <!--wb_else_if wb_xxx--> : {}.",
+                    value
+                )
+            }
+            TemplateErrorKind::BadMarkerPrefix { attribute, value } => write!(
+                f,
+                "{} value does not start with {} : {}.",
+                attribute,
+                expected_prefix(attribute),
+                value
+            ),
+            TemplateErrorKind::BadListenerArguments { attribute, value } => write!(
+                f,
+                "{} has malformed arguments {}, use wl_xxx(arg1, arg2)",
+                attribute, value
+            ),
+            TemplateErrorKind::UnknownListenerModifier {
+                attribute,
+                modifier,
+            } => write!(
+                f,
+                "{} has unknown modifier .{}, use prevent, stop, once, self, debounce-300ms, throttle-100ms or a key name",
+                attribute, modifier
+            ),
+            TemplateErrorKind::DuplicateKey(key) => write!(
+                f,
+                "data-key=\"{}\" is used twice, the keys of the sibling elements must be unique",
+                key
+            ),
+            TemplateErrorKind::ElseWithoutIf(marker) => write!(
+                f,
+                "<!--{}--> must be after an element with <!--wb_xxx--> or <!--wb_else_if wb_xxx-->",
+                marker
+            ),
+            TemplateErrorKind::UnexpectedEndOfTemplate => write!(f, "unexpected end of template"),
+            TemplateErrorKind::SubTemplateRoot(name) => write!(
+                f,
+                "sub_template {} must have exactly one root element",
+                name
+            ),
+            TemplateErrorKind::DuplicateSubTemplate(name) => {
+                write!(f, "sub_template {} is defined more than once", name)
+            }
+            TemplateErrorKind::UnknownSubTemplate(name) => {
+                write!(f, "sub_template {} does not exist", name)
+            }
+            TemplateErrorKind::UnknownInclude { include, chain } => {
+                if chain.is_empty() {
+                    write!(f, "include {} is not resolved", include)
+                } else {
+                    write!(f, "include {} does not exist: {}", include, chain)
+                }
+            }
+            TemplateErrorKind::IncludeCycle(chain) => {
+                write!(f, "include cycle: {}", chain)
+            }
+            TemplateErrorKind::UnknownLayout { layout, chain } => {
+                write!(f, "layout {} does not exist: {}", layout, chain)
+            }
+            TemplateErrorKind::LayoutCycle(chain) => write!(f, "layout cycle: {}", chain),
+            TemplateErrorKind::UnknownBlock { block, layout } => {
+                write!(f, "block {} does not exist in layout {}", block, layout)
+            }
+            TemplateErrorKind::ParserError(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // like the compiler: name:line:column:
+        // the errors outside of the template text have no line and column
+        let mut position = vec![];
+        if !self.template_name.is_empty() {
+            position.push(self.template_name.clone());
+        }
+        if self.line > 0 {
+            position.push(self.line.to_string());
+            position.push(self.column.to_string());
+        }
+        if !position.is_empty() {
+            write!(f, "{}: ", position.join(":"))?;
+        }
+        write!(f, "Error: {}", self.kind)?;
+        if !self.dom_path.is_empty() {
+            write!(f, " in <{}>", self.dom_path.join("> <"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// The old api returned errors as String.
+/// This conversion allows to use `?` in functions that still return String.
+impl From<TemplateError> for String {
+    fn from(err: TemplateError) -> String {
+        err.to_string()
+    }
+}
+
+/// the prefix of the marker name depends on the special attribute
+pub fn expected_prefix(attribute: &str) -> &'static str {
+    if attribute.starts_with("data-on-") {
+        "wl_"
+    } else if attribute.starts_with("data-wb-")
+        || attribute.starts_with("data-wc-")
+        || attribute.starts_with("wb_")
+    {
+        "wb_"
+    } else {
+        "wt_"
+    }
+}
+
+/// converts the byte offset into line and column, both start with 1
+pub(crate) fn line_and_column(html_template: &str, offset: usize) -> (usize, usize) {
+    let before = html_template.get(..offset).unwrap_or(html_template);
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    let column = before[line_start..].chars().count() + 1;
+    // return
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_and_column_lines() {
+        let html_template = "<div>\n  <p>\n</div>";
+        assert_eq!(line_and_column(html_template, 0), (1, 1));
+        assert_eq!(line_and_column(html_template, 8), (2, 3));
+        assert_eq!(line_and_column(html_template, 12), (3, 1));
+    }
+
+    #[test]
+    fn line_and_column_counts_chars() {
+        // č and š have 2 bytes, the column counts characters
+        let html_template = "čš<b>";
+        assert_eq!(line_and_column(html_template, 4), (1, 3));
+        // the offset after the end is the end
+        assert_eq!(line_and_column(html_template, 100), (1, 6));
+    }
+}
@@ -0,0 +1,241 @@
+//! **template_include_mod**
+//! One template can include another one with the marker <!--wi_xxx-->.
+//! Shared headers, footers and dialogs are written only once in their own template.
+//! The templates are found by name in a TemplateSource. The project decides
+//! where the templates come from: files, fetched strings or a TemplateLibrary.
+//! The includes are resolved once after parsing. The marker is replaced with
+//! the root element of the included template, so the walkers never see it.
+
+// region: use
+use crate::html_entity_mod::EntityDecoding;
+use crate::parsed_template_mod::*;
+use crate::template_error_mod::*;
+use crate::template_library_mod::TemplateLibrary;
+use std::borrow::Cow;
+use std::collections::HashMap;
+// endregion: use
+
+/// Where the included templates come from.
+pub trait TemplateSource {
+    /// The parsed template by name or None if there is no template with this name.
+    /// The entity_decoding is used if the template must be parsed.
+    fn get_template(
+        &self,
+        name: &str,
+        entity_decoding: EntityDecoding,
+    ) -> Result<Option<Cow<'_, ParsedTemplate>>, TemplateError>;
+}
+
+/// The html templates by name, parsed when they are included.
+/// The build.rs code generator uses all the templates as source.
+impl TemplateSource for HashMap<String, String> {
+    fn get_template(
+        &self,
+        name: &str,
+        entity_decoding: EntityDecoding,
+    ) -> Result<Option<Cow<'_, ParsedTemplate>>, TemplateError> {
+        match self.get(name) {
+            Some(html_template) => Ok(Some(Cow::Owned(ParsedTemplate::parse_with_decoding(
+                name,
+                html_template,
+                entity_decoding,
+            )?))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The sub_templates are already parsed.
+impl TemplateSource for TemplateLibrary {
+    fn get_template(
+        &self,
+        name: &str,
+        _entity_decoding: EntityDecoding,
+    ) -> Result<Option<Cow<'_, ParsedTemplate>>, TemplateError> {
+        Ok(self.get(name).map(Cow::Borrowed))
+    }
+}
+
+impl ParsedTemplate {
+    /// Replaces all the include markers <!--wi_xxx--> with the root element
+    /// of the template xxx from the source. Also the includes inside the included templates.
+    /// The name of this template is the start of the include chain in the error messages.
+    pub fn resolve_includes(
+        &mut self,
+        template_name: &str,
+        source: &dyn TemplateSource,
+        entity_decoding: EntityDecoding,
+    ) -> Result<(), TemplateError> {
+        let mut resolver = IncludeResolver {
+            template_name,
+            source,
+            entity_decoding,
+            chain: vec![template_name.to_owned()],
+            dom_path: vec![self.root.name.clone()],
+        };
+        resolver.resolve(&mut self.root)
+    }
+
+    /// true if the template has some <!--wi_xxx--> markers
+    pub fn has_includes(&self) -> bool {
+        element_has_includes(&self.root)
+    }
+}
+
+/// The resolver keeps the include chain and the dom_path for the error messages.
+struct IncludeResolver<'s> {
+    template_name: &'s str,
+    source: &'s dyn TemplateSource,
+    entity_decoding: EntityDecoding,
+    /// names of the templates from the outermost to the one that is resolved now
+    chain: Vec<String>,
+    dom_path: Vec<String>,
+}
+
+impl<'s> IncludeResolver<'s> {
+    /// Recursive function that replaces the include markers in the children of this element.
+    fn resolve(&mut self, element: &mut TemplateElement) -> Result<(), TemplateError> {
+        for child in element.children.iter_mut() {
+            match child {
+                TemplateNode::Element(child_element) => {
+                    self.dom_path.push(child_element.name.clone());
+                    self.resolve(child_element)?;
+                    self.dom_path.pop();
+                }
+                TemplateNode::Include(include_name) => {
+                    let root = self.included_root(&include_name.clone())?;
+                    *child = TemplateNode::Element(root);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// the root element of the included template with its includes already resolved
+    fn included_root(&mut self, include_name: &str) -> Result<TemplateElement, TemplateError> {
+        if self.chain.iter().any(|name| name == include_name) {
+            let chain = format!("{} -> {}", self.chain_text(), include_name);
+            return Err(self.error(TemplateErrorKind::IncludeCycle(chain)));
+        }
+        let included = match self
+            .source
+            .get_template(include_name, self.entity_decoding)?
+        {
+            Some(included) => included,
+            None => {
+                return Err(self.error(TemplateErrorKind::UnknownInclude {
+                    include: include_name.to_owned(),
+                    chain: self.chain_text(),
+                }))
+            }
+        };
+        let mut root = included.into_owned().root;
+        // the included template has its own dom_path
+        let dom_path = std::mem::replace(&mut self.dom_path, vec![root.name.clone()]);
+        self.chain.push(include_name.to_owned());
+        let result = self.resolve(&mut root);
+        self.chain.pop();
+        self.dom_path = dom_path;
+        result?;
+        // return
+        Ok(root)
+    }
+
+    /// the chain of includes like index -> header -> menu
+    fn chain_text(&self) -> String {
+        self.chain
+            .iter()
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(" -> ")
+    }
+
+    /// The parsed tree has no positions, the include chain and the dom_path show the place.
+    fn error(&self, kind: TemplateErrorKind) -> TemplateError {
+        TemplateError {
+            kind,
+            template_name: self.template_name.to_owned(),
+            line: 0,
+            column: 0,
+            dom_path: self.dom_path.clone(),
+        }
+    }
+}
+
+/// recursive search for the include markers
+fn element_has_includes(element: &TemplateElement) -> bool {
+    element.children.iter().any(|child| match child {
+        TemplateNode::Element(child_element) => element_has_includes(child_element),
+        TemplateNode::Include(_) => true,
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html_template_mod::HtmlOrSvg;
+    use crate::server_render_mod::render_to_string;
+    use crate::test_harness_mod::MockTemplating;
+    use unwrap::unwrap;
+
+    fn source(templates: &[(&str, &str)]) -> HashMap<String, String> {
+        templates
+            .iter()
+            .map(|(name, html_template)| (name.to_string(), html_template.to_string()))
+            .collect()
+    }
+
+    fn resolve(html_template: &str, templates: &[(&str, &str)]) -> Result<String, TemplateError> {
+        let mut parsed_template = ParsedTemplate::parse_named("page", html_template)?;
+        parsed_template.resolve_includes("page", &source(templates), EntityDecoding::default())?;
+        render_to_string(
+            &MockTemplating::default(),
+            &parsed_template,
+            HtmlOrSvg::Html,
+        )
+    }
+
+    #[test]
+    fn include_replaces_the_marker_also_nested() {
+        let html = unwrap!(resolve(
+            "<div><!--wi_header--><p>page</p></div>",
+            &[
+                ("header", "<header><!--wi_logo--></header>"),
+                ("logo", r#"<span class="logo">logo</span>"#),
+            ],
+        ));
+        assert_eq!(
+            html,
+            r#"<div><header><span class="logo">logo</span></header><p>page</p></div>"#
+        );
+    }
+
+    #[test]
+    fn unknown_include_and_cycle_are_errors() {
+        let err = unwrap!(resolve("<div><!--wi_missing--><br /></div>", &[]).err());
+        assert!(matches!(err.kind, TemplateErrorKind::UnknownInclude { .. }));
+        let err = unwrap!(resolve(
+            "<div><!--wi_a--><br /></div>",
+            &[
+                ("a", "<p><!--wi_b--><br /></p>"),
+                ("b", "<p><!--wi_a--><br /></p>"),
+            ],
+        )
+        .err());
+        assert!(matches!(err.kind, TemplateErrorKind::IncludeCycle(_)));
+    }
+
+    #[test]
+    fn library_is_a_template_source() {
+        let library = unwrap!(TemplateLibrary::parse(
+            r#"<div><template name="item"><li>item</li></template><ul><!--wi_item--><br /></ul></div>"#
+        ));
+        let mut parsed_template = library.main_template().clone();
+        assert!(parsed_template.has_includes());
+        unwrap!(parsed_template.resolve_includes("page", &library, EntityDecoding::default()));
+        assert!(!parsed_template.has_includes());
+    }
+}
@@ -0,0 +1,251 @@
+//! **template_layout_mod**
+//! The pages of a project share the same outer page chrome.
+//! The layout template contains the chrome and the named blocks `data-block="xxx"`.
+//! The page template declares its layout on the root element `data-layout="layout_name"`
+//! and contains only the blocks it overrides.
+//! The layout is resolved once after parsing, before the walkers see the tree.
+
+// region: use
+use crate::html_entity_mod::EntityDecoding;
+use crate::parsed_template_mod::*;
+use crate::template_error_mod::*;
+use crate::template_include_mod::TemplateSource;
+// endregion: use
+
+/// the attribute on the root element of the page
+pub const ATTR_LAYOUT: &str = "data-layout";
+/// the attribute on the elements of the layout and of the page
+pub const ATTR_BLOCK: &str = "data-block";
+
+impl ParsedTemplate {
+    /// If the root element has the attribute data-layout="layout_name"
+    /// this template is replaced with the layout from the source.
+    /// The content of the blocks data-block="xxx" in the layout is replaced
+    /// with the content of the same blocks in this page.
+    /// The layout can have its own layout.
+    pub fn resolve_layout(
+        &mut self,
+        template_name: &str,
+        source: &dyn TemplateSource,
+        entity_decoding: EntityDecoding,
+    ) -> Result<(), TemplateError> {
+        let resolver = LayoutResolver {
+            template_name,
+            source,
+            entity_decoding,
+        };
+        let mut chain = vec![template_name.to_owned()];
+        if let Some(mut root) = resolver.resolve(&self.root, &mut chain)? {
+            remove_block_attributes(&mut root);
+            self.root = root;
+        }
+        Ok(())
+    }
+}
+
+/// The resolver keeps what is needed for the error messages.
+struct LayoutResolver<'s> {
+    template_name: &'s str,
+    source: &'s dyn TemplateSource,
+    entity_decoding: EntityDecoding,
+}
+
+impl<'s> LayoutResolver<'s> {
+    /// Recursive function that returns the layout with the blocks of the page.
+    /// None if the page has no layout.
+    /// The chain contains the names from the page to the outermost layout.
+    fn resolve(
+        &self,
+        page_root: &TemplateElement,
+        chain: &mut Vec<String>,
+    ) -> Result<Option<TemplateElement>, TemplateError> {
+        let layout_name = match attribute_value(page_root, ATTR_LAYOUT) {
+            Some(layout_name) => layout_name,
+            None => return Ok(None),
+        };
+        if chain.iter().any(|name| name == layout_name) {
+            chain.push(layout_name.to_owned());
+            return Err(self.error(TemplateErrorKind::LayoutCycle(chain_text(chain))));
+        }
+        let layout = match self
+            .source
+            .get_template(layout_name, self.entity_decoding)?
+        {
+            Some(layout) => layout,
+            None => {
+                return Err(self.error(TemplateErrorKind::UnknownLayout {
+                    layout: layout_name.to_owned(),
+                    chain: chain_text(chain),
+                }))
+            }
+        };
+        chain.push(layout_name.to_owned());
+        // the layout of the layout first, the blocks of this page are the last word
+        let mut layout_root = match self.resolve(&layout.root, chain)? {
+            Some(layout_root) => layout_root,
+            None => layout.into_owned().root,
+        };
+        chain.pop();
+        let mut page_blocks = vec![];
+        collect_blocks(page_root, &mut page_blocks);
+        let mut used = vec![false; page_blocks.len()];
+        replace_blocks(&mut layout_root, &page_blocks, &mut used);
+        if let Some(index) = used.iter().position(|is_used| !is_used) {
+            return Err(self.error(TemplateErrorKind::UnknownBlock {
+                block: page_blocks[index].0.to_owned(),
+                layout: layout_name.to_owned(),
+            }));
+        }
+        // return
+        Ok(Some(layout_root))
+    }
+
+    /// The parsed tree has no positions, the chain of layouts shows the place.
+    fn error(&self, kind: TemplateErrorKind) -> TemplateError {
+        TemplateError {
+            kind,
+            template_name: self.template_name.to_owned(),
+            line: 0,
+            column: 0,
+            dom_path: vec![],
+        }
+    }
+}
+
+/// the value of the static attribute
+fn attribute_value<'e>(element: &'e TemplateElement, attr_name: &str) -> Option<&'e str> {
+    element
+        .attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            TemplateAttribute::Static { name, value } if name == attr_name => Some(value.as_str()),
+            _ => None,
+        })
+}
+
+/// the outermost blocks of the page, the nested blocks are part of their content
+fn collect_blocks<'e>(
+    element: &'e TemplateElement,
+    blocks: &mut Vec<(&'e str, &'e TemplateElement)>,
+) {
+    for child in &element.children {
+        if let TemplateNode::Element(child_element) = child {
+            match attribute_value(child_element, ATTR_BLOCK) {
+                Some(block_name) => blocks.push((block_name, child_element)),
+                None => collect_blocks(child_element, blocks),
+            }
+        }
+    }
+}
+
+/// Recursive function that replaces the content of the blocks in the layout.
+/// The element of the layout stays with its attributes, only the children are replaced.
+fn replace_blocks(
+    element: &mut TemplateElement,
+    page_blocks: &[(&str, &TemplateElement)],
+    used: &mut [bool],
+) {
+    for child in element.children.iter_mut() {
+        if let TemplateNode::Element(child_element) = child {
+            let page_block = attribute_value(child_element, ATTR_BLOCK).and_then(|block_name| {
+                page_blocks
+                    .iter()
+                    .position(|(page_block_name, _)| *page_block_name == block_name)
+            });
+            match page_block {
+                Some(index) => {
+                    used[index] = true;
+                    child_element.children = page_blocks[index].1.children.clone();
+                }
+                None => replace_blocks(child_element, page_blocks, used),
+            }
+        }
+    }
+}
+
+/// the final tree does not need the data-block attributes
+fn remove_block_attributes(element: &mut TemplateElement) {
+    element.attributes.retain(|attribute| {
+        !matches!(attribute, TemplateAttribute::Static { name, .. } if name == ATTR_BLOCK)
+    });
+    for child in element.children.iter_mut() {
+        if let TemplateNode::Element(child_element) = child {
+            remove_block_attributes(child_element);
+        }
+    }
+}
+
+/// the chain of layouts like page -> layout -> base
+fn chain_text(chain: &[String]) -> String {
+    chain
+        .iter()
+        .filter(|name| !name.is_empty())
+        .cloned()
+        .collect::<Vec<String>>()
+        .join(" -> ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html_template_mod::HtmlOrSvg;
+    use crate::server_render_mod::render_to_string;
+    use crate::test_harness_mod::MockTemplating;
+    use std::collections::HashMap;
+    use unwrap::unwrap;
+
+    const LAYOUT: &str = r#"<html><body><nav>menu</nav><main data-block="content">default</main><footer data-block="footer">footer</footer></body></html>"#;
+
+    fn resolve(html_template: &str, templates: &[(&str, &str)]) -> Result<String, TemplateError> {
+        let source: HashMap<String, String> = templates
+            .iter()
+            .map(|(name, html_template)| (name.to_string(), html_template.to_string()))
+            .collect();
+        let mut parsed_template = ParsedTemplate::parse_named("page", html_template)?;
+        parsed_template.resolve_layout("page", &source, EntityDecoding::default())?;
+        render_to_string(
+            &MockTemplating::default(),
+            &parsed_template,
+            HtmlOrSvg::Html,
+        )
+    }
+
+    #[test]
+    fn page_overrides_only_its_blocks() {
+        let html = unwrap!(resolve(
+            r#"<div data-layout="layout"><main data-block="content"><p>page</p></main></div>"#,
+            &[("layout", LAYOUT)],
+        ));
+        assert_eq!(
+            html,
+            "<html><body><nav>menu</nav><main><p>page</p></main><footer>footer</footer></body></html>"
+        );
+    }
+
+    #[test]
+    fn page_without_layout_stays() {
+        let html = unwrap!(resolve("<div><p>page</p></div>", &[]));
+        assert_eq!(html, "<div><p>page</p></div>");
+    }
+
+    #[test]
+    fn layout_errors() {
+        let err = unwrap!(resolve(r#"<div data-layout="missing"></div>"#, &[]).err());
+        assert!(matches!(err.kind, TemplateErrorKind::UnknownLayout { .. }));
+        let err = unwrap!(resolve(
+            r#"<div data-layout="layout"><p data-block="sidebar">x</p></div>"#,
+            &[("layout", LAYOUT)],
+        )
+        .err());
+        assert!(matches!(err.kind, TemplateErrorKind::UnknownBlock { .. }));
+        let err = unwrap!(resolve(
+            r#"<div data-layout="a"></div>"#,
+            &[
+                ("a", r#"<div data-layout="b"></div>"#),
+                ("b", r#"<div data-layout="a"></div>"#),
+            ],
+        )
+        .err());
+        assert!(matches!(err.kind, TemplateErrorKind::LayoutCycle(_)));
+    }
+}
@@ -0,0 +1,256 @@
+//! **template_library_mod**
+//! The html template contains named sub_templates in `<template name="xxx">` elements.
+//! The TemplateLibrary splits them out of the main template once after loading.
+//! The sub_templates can be nested. Every one is removed from its parent
+//! and is available by name for rendering, usually for the <!--wn_xxx--> replacement.
+
+// region: use
+use crate::html_entity_mod::EntityDecoding;
+use crate::parsed_template_mod::*;
+use crate::template_error_mod::*;
+// endregion: use
+
+/// The main template and the named sub_templates, all parsed once.
+#[derive(Clone, Debug)]
+pub struct TemplateLibrary {
+    /// name of the template file for the error messages, can be empty
+    template_name: String,
+    /// the main template without the sub_templates
+    main_template: ParsedTemplate,
+    /// sub_templates in the order of the html template
+    sub_templates: Vec<(String, ParsedTemplate)>,
+}
+
+impl TemplateLibrary {
+    /// Parses the full html template and splits out the sub_templates.
+    pub fn parse(html_template: &str) -> Result<TemplateLibrary, TemplateError> {
+        Self::parse_named("", html_template)
+    }
+
+    /// Parses the full html template and splits out the sub_templates.
+    /// The name of the template is used in the error messages.
+    pub fn parse_named(
+        template_name: &str,
+        html_template: &str,
+    ) -> Result<TemplateLibrary, TemplateError> {
+        Self::parse_with_decoding(template_name, html_template, EntityDecoding::default())
+    }
+
+    /// Parses the full html template with the entities decoded as defined by entity_decoding.
+    pub fn parse_with_decoding(
+        template_name: &str,
+        html_template: &str,
+        entity_decoding: EntityDecoding,
+    ) -> Result<TemplateLibrary, TemplateError> {
+        let mut main_template =
+            ParsedTemplate::parse_with_decoding(template_name, html_template, entity_decoding)?;
+        let mut extractor = SubTemplateExtractor {
+            template_name,
+            html_template,
+            dom_path: vec![main_template.root.name.clone()],
+            sub_templates: vec![],
+        };
+        extractor.extract(&mut main_template.root)?;
+        // return
+        Ok(TemplateLibrary {
+            template_name: template_name.to_owned(),
+            main_template,
+            sub_templates: extractor.sub_templates,
+        })
+    }
+
+    /// the main template without the sub_templates
+    pub fn main_template(&self) -> &ParsedTemplate {
+        &self.main_template
+    }
+
+    /// the sub_template by name
+    pub fn get(&self, name: &str) -> Option<&ParsedTemplate> {
+        self.sub_templates
+            .iter()
+            .find(|(sub_name, _)| sub_name == name)
+            .map(|(_, sub_template)| sub_template)
+    }
+
+    /// the sub_template by name or the error UnknownSubTemplate
+    pub fn get_or_error(&self, name: &str) -> Result<&ParsedTemplate, TemplateError> {
+        self.get(name).ok_or_else(|| TemplateError {
+            kind: TemplateErrorKind::UnknownSubTemplate(name.to_owned()),
+            template_name: self.template_name.clone(),
+            line: 0,
+            column: 0,
+            dom_path: vec![],
+        })
+    }
+
+    /// names of all sub_templates in the order of the html template
+    pub fn names(&self) -> Vec<&str> {
+        self.sub_templates
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// The extractor walks the parsed tree and keeps the dom_path for the error messages.
+struct SubTemplateExtractor<'t> {
+    template_name: &'t str,
+    html_template: &'t str,
+    dom_path: Vec<String>,
+    sub_templates: Vec<(String, ParsedTemplate)>,
+}
+
+impl<'t> SubTemplateExtractor<'t> {
+    /// Recursive function that removes the named sub_templates from the children
+    /// of this element. The nested sub_templates are removed before
+    /// the outer sub_template is stored.
+    fn extract(&mut self, element: &mut TemplateElement) -> Result<(), TemplateError> {
+        let children = std::mem::take(&mut element.children);
+        for child in children {
+            match child {
+                TemplateNode::Element(mut child_element) => {
+                    self.dom_path.push(child_element.name.clone());
+                    match sub_template_name(&child_element) {
+                        Some(name) => {
+                            let name = name.to_owned();
+                            // the outer sub_template is listed before the nested ones
+                            let index = self.sub_templates.len();
+                            let root = self.sub_template_root(&name, &mut child_element)?;
+                            self.sub_templates
+                                .insert(index, (name, ParsedTemplate { root }));
+                        }
+                        None => {
+                            self.extract(&mut child_element)?;
+                            element.children.push(TemplateNode::Element(child_element));
+                        }
+                    }
+                    self.dom_path.pop();
+                }
+                other => element.children.push(other),
+            }
+        }
+        Ok(())
+    }
+
+    /// the only child of <template name="xxx"> is the root of the sub_template
+    fn sub_template_root(
+        &mut self,
+        name: &str,
+        template_element: &mut TemplateElement,
+    ) -> Result<TemplateElement, TemplateError> {
+        if self
+            .sub_templates
+            .iter()
+            .any(|(sub_name, _)| sub_name == name)
+        {
+            return Err(self.error(
+                TemplateErrorKind::DuplicateSubTemplate(name.to_owned()),
+                name,
+            ));
+        }
+        let mut children = std::mem::take(&mut template_element.children);
+        match (children.pop(), children.is_empty()) {
+            (Some(TemplateNode::Element(mut root)), true) => {
+                self.dom_path.push(root.name.clone());
+                self.extract(&mut root)?;
+                self.dom_path.pop();
+                // return
+                Ok(root)
+            }
+            _ => Err(self.error(TemplateErrorKind::SubTemplateRoot(name.to_owned()), name)),
+        }
+    }
+
+    /// The parsed tree has no positions.
+    /// The position of the error is the start tag of the sub_template in the html template.
+    fn error(&self, kind: TemplateErrorKind, name: &str) -> TemplateError {
+        let offset = self
+            .html_template
+            .match_indices("<template")
+            .map(|(pos, _)| pos)
+            .filter(|pos| {
+                let start_tag = &self.html_template[*pos..];
+                let start_tag = &start_tag[..start_tag.find('>').unwrap_or(start_tag.len())];
+                start_tag.contains(&format!("\"{}\"", name))
+                    || start_tag.contains(&format!("'{}'", name))
+            })
+            .last()
+            .unwrap_or(0);
+        TemplateError::new(
+            kind,
+            self.template_name,
+            self.html_template,
+            offset,
+            &self.dom_path,
+        )
+    }
+}
+
+/// the value of the name attribute, if the element is <template name="xxx">
+fn sub_template_name(element: &TemplateElement) -> Option<&str> {
+    if element.name != "template" {
+        return None;
+    }
+    element
+        .attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            TemplateAttribute::Static { name, value } if name == "name" => Some(value.as_str()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html_template_mod::HtmlOrSvg;
+    use crate::server_render_mod::render_to_string;
+    use crate::test_harness_mod::MockTemplating;
+    use unwrap::unwrap;
+
+    const TEMPLATE: &str = r#"<div><p>main</p><template name="card"><div class="card"><template name="badge"><b>badge</b></template><p>card</p></div></template></div>"#;
+
+    fn render(parsed_template: &ParsedTemplate) -> String {
+        unwrap!(render_to_string(
+            &MockTemplating::default(),
+            parsed_template,
+            HtmlOrSvg::Html
+        ))
+    }
+
+    #[test]
+    fn sub_templates_are_split_out_also_nested() {
+        let library = unwrap!(TemplateLibrary::parse(TEMPLATE));
+        assert_eq!(library.names(), vec!["card", "badge"]);
+        assert_eq!(render(library.main_template()), "<div><p>main</p></div>");
+        assert_eq!(
+            render(unwrap!(library.get("card"))),
+            r#"<div class="card"><p>card</p></div>"#
+        );
+        assert_eq!(render(unwrap!(library.get("badge"))), "<b>badge</b>");
+    }
+
+    #[test]
+    fn sub_template_errors() {
+        let library = unwrap!(TemplateLibrary::parse_named("page", TEMPLATE));
+        let err = unwrap!(library.get_or_error("missing").err());
+        assert_eq!(
+            err.kind,
+            TemplateErrorKind::UnknownSubTemplate("missing".to_owned())
+        );
+        assert_eq!(err.template_name, "page");
+        let err = unwrap!(TemplateLibrary::parse(
+            r#"<div><template name="x"><b>1</b></template><template name="x"><b>2</b></template></div>"#
+        )
+        .err());
+        assert_eq!(
+            err.kind,
+            TemplateErrorKind::DuplicateSubTemplate("x".to_owned())
+        );
+        let err = unwrap!(TemplateLibrary::parse(
+            r#"<div><template name="x"><b>1</b><i>2</i></template></div>"#
+        )
+        .err());
+        assert!(matches!(err.kind, TemplateErrorKind::SubTemplateRoot(_)));
+    }
+}
@@ -0,0 +1,486 @@
+//! **template_lint_mod**
+//! Typos in the markers like <!--wt_nick_nmae--> are found only in runtime,
+//! when replace_with_string() falls into the catch-all match arm.
+//! The lint lists every marker in the templates with its position
+//! and compares the names with the names implemented in the Rust code.
+//! The binary `template_lint` uses it from the command line.
+
+// region: use
+use crate::parsed_template_mod::{
+    else_if_name, split_listener_call, split_listener_values, ParsedTemplate,
+};
+use crate::template_error_mod::*;
+use reader_for_microxml::*;
+// endregion: use
+
+/// the prefixes of the marker names, that must be implemented in Rust code
+pub const MARKER_PREFIXES: [&str; 5] = ["wt_", "wn_", "wb_", "wr_", "wl_"];
+
+/// What the marker replaces in the template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkerKind {
+    /// wt_ replaces a text node
+    Text,
+    /// wt_ replaces an attribute value
+    Attribute,
+    /// wn_ replaces a node with nodes
+    Nodes,
+    /// wb_ retains or removes a node, an attribute or a class
+    Boolean,
+    /// wl_ event listener
+    Listener,
+    /// wr_ repeats a node
+    Repeat,
+}
+
+impl MarkerKind {
+    /// the kind of a marker in a comment <!--xxx--> from the prefix
+    fn from_comment(txt: &str) -> MarkerKind {
+        if txt.starts_with("wn_") {
+            MarkerKind::Nodes
+        } else if txt.starts_with("wb_") {
+            MarkerKind::Boolean
+        } else if txt.starts_with("wr_") {
+            MarkerKind::Repeat
+        } else if txt.starts_with("wl_") {
+            MarkerKind::Listener
+        } else {
+            MarkerKind::Text
+        }
+    }
+}
+
+impl std::fmt::Display for MarkerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            MarkerKind::Text => "text",
+            MarkerKind::Attribute => "attribute",
+            MarkerKind::Nodes => "nodes",
+            MarkerKind::Boolean => "boolean",
+            MarkerKind::Listener => "listener",
+            MarkerKind::Repeat => "repeat",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// One marker in the template with its position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkerLocation {
+    /// the name of the marker like wt_nick_name
+    pub name: String,
+    /// what the marker replaces
+    pub kind: MarkerKind,
+    /// how it is written in the template like <!--wt_nick_name--> or data-wt-href
+    pub context: String,
+    /// name of the template
+    pub template_name: String,
+    /// line in the template, starts with 1
+    pub line: usize,
+    /// column in the line, starts with 1
+    pub column: usize,
+    /// names of the open elements, from the root element
+    pub dom_path: Vec<String>,
+}
+
+impl std::fmt::Display for MarkerLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} {}",
+            self.template_name, self.line, self.column, self.kind, self.context
+        )?;
+        if !self.dom_path.is_empty() {
+            write!(f, " in <{}>", self.dom_path.join("> <"))?;
+        }
+        Ok(())
+    }
+}
+
+/// All markers of one template and the malformed ones as errors.
+#[derive(Clone, Debug, Default)]
+pub struct TemplateLint {
+    pub markers: Vec<MarkerLocation>,
+    pub errors: Vec<TemplateError>,
+}
+
+/// Lists the markers in the html template.
+/// The lint does not stop on the first malformed marker,
+/// but it stops on the first error of the microxml parser.
+pub fn lint_template(template_name: &str, html_template: &str) -> TemplateLint {
+    let mut scanner = MarkerScanner {
+        template_name,
+        html_template,
+        dom_path: vec![],
+        lint: TemplateLint::default(),
+    };
+    if !html_template.is_empty() {
+        scanner.scan();
+    }
+    // the structural errors like end tags and else markers come from the parser
+    // the malformed prefixes and listener arguments are already listed
+    if let Err(err) = ParsedTemplate::parse_named(template_name, html_template) {
+        if !matches!(
+            err.kind,
+            TemplateErrorKind::BadMarkerPrefix { .. }
+                | TemplateErrorKind::BadListenerArguments { .. }
+        ) {
+            scanner.lint.errors.push(err);
+        }
+    }
+    // return
+    scanner.lint
+}
+
+/// The names of the markers implemented in the Rust code.
+/// They are the string literals that start with a marker prefix,
+/// usually in the match arms of replace_with_string() and the other methods.
+/// The methods with the derive attributes #[wt], #[wb], #[wn] and #[wl] are markers too,
+/// with the same name rule as in the derive: the prefix is added when the method name does not start with it.
+pub fn names_in_rust_code(rust_code: &str) -> Vec<String> {
+    // the position in the code keeps the names in order
+    let mut found: Vec<(usize, String)> = vec![];
+    for (pos, _) in rust_code.match_indices('"') {
+        let rest = &rust_code[pos + 1..];
+        if !MARKER_PREFIXES
+            .iter()
+            .any(|prefix| rest.starts_with(prefix))
+        {
+            continue;
+        }
+        let end = rest
+            .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .unwrap_or(rest.len());
+        if rest[end..].starts_with('"') {
+            found.push((pos, rest[..end].to_owned()));
+        }
+    }
+    for (attribute, prefix) in MARKER_ATTRIBUTES {
+        for (pos, _) in rust_code.match_indices(attribute) {
+            if let Some(method_name) = next_fn_name(&rust_code[pos + attribute.len()..]) {
+                let name = if method_name.starts_with(prefix) {
+                    method_name.to_owned()
+                } else {
+                    format!("{}{}", prefix, method_name)
+                };
+                found.push((pos, name));
+            }
+        }
+    }
+    found.sort_by_key(|(pos, _)| *pos);
+    let mut names = vec![];
+    for (_, name) in &found {
+        push_unique(&mut names, name);
+    }
+    // return
+    names
+}
+
+/// the derive attributes without the name argument and the prefix of their marker
+const MARKER_ATTRIBUTES: [(&str, &str); 4] = [
+    ("#[wt]", "wt_"),
+    ("#[wb]", "wb_"),
+    ("#[wn]", "wn_"),
+    ("#[wl]", "wl_"),
+];
+
+/// the name of the method after the attribute, other attributes and pub can be between
+fn next_fn_name(rust_code: &str) -> Option<&str> {
+    let mut words = rust_code
+        .split(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+        .filter(|word| !word.is_empty());
+    words.find(|word| *word == "fn")?;
+    // return
+    words.next()
+}
+
+/// The names of the markers from the manifest file.
+/// One name per line. Empty lines and lines starting with # are ignored.
+pub fn names_in_manifest(manifest: &str) -> Vec<String> {
+    let mut names = vec![];
+    for line in manifest.lines() {
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            push_unique(&mut names, line);
+        }
+    }
+    // return
+    names
+}
+
+/// The markers used in templates, that are not in the known names.
+pub fn unknown_markers<'m>(
+    markers: &'m [MarkerLocation],
+    known_names: &[String],
+) -> Vec<&'m MarkerLocation> {
+    markers
+        .iter()
+        .filter(|marker| !known_names.contains(&marker.name))
+        .collect()
+}
+
+/// The known names, that are not used in any template.
+pub fn unused_names<'n>(markers: &[MarkerLocation], known_names: &'n [String]) -> Vec<&'n str> {
+    known_names
+        .iter()
+        .filter(|name| !markers.iter().any(|marker| &marker.name == *name))
+        .map(|name| name.as_str())
+        .collect()
+}
+
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|x| x == name) {
+        names.push(name.to_owned());
+    }
+}
+
+/// The scanner reads the tokens and knows their position in the template.
+struct MarkerScanner<'t> {
+    template_name: &'t str,
+    html_template: &'t str,
+    dom_path: Vec<String>,
+    lint: TemplateLint,
+}
+
+impl<'t> MarkerScanner<'t> {
+    fn scan(&mut self) {
+        let mut reader_for_microxml = ReaderForMicroXml::new(self.html_template);
+        for result_token in &mut reader_for_microxml {
+            let token = match result_token {
+                Ok(token) => token,
+                // the parser error is reported by lint_template()
+                Err(_) => return,
+            };
+            match token {
+                Token::StartElement(name) => self.dom_path.push(name.to_owned()),
+                Token::EndElement(_) => {
+                    self.dom_path.pop();
+                }
+                Token::Attribute(attr_name, value) => self.scan_attribute(attr_name, value),
+                Token::TextNode(txt) => self.scan_placeholders(txt, "text", MarkerKind::Text),
+                Token::Comment(txt) => self.scan_comment(txt),
+            }
+        }
+    }
+
+    fn scan_attribute(&mut self, attr_name: &'t str, value: &'t str) {
+        let is_special = ["data-wt-", "data-wb-", "data-wc-", "data-on-"]
+            .iter()
+            .any(|prefix| attr_name.starts_with(prefix));
+        if !is_special {
+            self.scan_placeholders(value, attr_name, MarkerKind::Attribute);
+        } else if attr_name.starts_with("data-on-") {
+            // an empty listener is allowed, more listeners are separated by whitespace
+            for listener_value in split_listener_values(value) {
+                let (fn_name, args) = match split_listener_call(listener_value) {
+                    Some(fn_name_and_args) => fn_name_and_args,
+                    None => {
+                        self.push_error(
+                            TemplateErrorKind::BadListenerArguments {
+                                attribute: attr_name.to_owned(),
+                                value: listener_value.to_owned(),
+                            },
+                            attr_name,
+                        );
+                        continue;
+                    }
+                };
+                if fn_name.starts_with("wl_") {
+                    self.push_marker(
+                        fn_name,
+                        MarkerKind::Listener,
+                        &format!("{}=\"{}\"", attr_name, listener_value),
+                        attr_name,
+                    );
+                } else {
+                    self.push_error(
+                        TemplateErrorKind::BadMarkerPrefix {
+                            attribute: attr_name.to_owned(),
+                            value: fn_name.to_owned(),
+                        },
+                        attr_name,
+                    );
+                }
+                if let Some(args) = args {
+                    self.scan_placeholders(args, attr_name, MarkerKind::Attribute);
+                }
+            }
+        } else if value.starts_with(expected_prefix(attr_name)) {
+            let kind = if attr_name.starts_with("data-wt-") {
+                MarkerKind::Attribute
+            } else {
+                MarkerKind::Boolean
+            };
+            self.push_marker(
+                value,
+                kind,
+                &format!("{}=\"{}\"", attr_name, value),
+                attr_name,
+            );
+        } else {
+            self.push_error(
+                TemplateErrorKind::BadMarkerPrefix {
+                    attribute: attr_name.to_owned(),
+                    value: value.to_owned(),
+                },
+                attr_name,
+            );
+        }
+    }
+
+    fn scan_comment(&mut self, txt: &'t str) {
+        if txt == "wb_else" {
+            // no name
+        } else if let Some(else_if) = else_if_name(txt) {
+            match else_if {
+                Ok(fn_name) => self.push_marker(
+                    fn_name,
+                    MarkerKind::Boolean,
+                    &format!("<!--{}-->", txt),
+                    txt,
+                ),
+                Err(kind) => self.push_error(kind, txt),
+            }
+        } else if MARKER_PREFIXES.iter().any(|prefix| txt.starts_with(prefix)) {
+            let kind = MarkerKind::from_comment(txt);
+            self.push_marker(txt, kind, &format!("<!--{}-->", txt), txt);
+        }
+    }
+
+    /// the inline placeholders {{wt_xxx}}
+    fn scan_placeholders(&mut self, txt: &'t str, context: &str, kind: MarkerKind) {
+        let mut rest = txt;
+        while let Some(start) = rest.find("{{") {
+            let after_start = &rest[start + 2..];
+            match after_start.find("}}") {
+                Some(end) if after_start[..end].trim().starts_with("wt_") => {
+                    let fn_name = after_start[..end].trim();
+                    self.push_marker(
+                        fn_name,
+                        kind,
+                        &format!("{{{{{}}}}} in {}", fn_name, context),
+                        &rest[start..],
+                    );
+                    rest = &after_start[end + 2..];
+                }
+                _ => rest = after_start,
+            }
+        }
+    }
+
+    fn push_marker(&mut self, name: &str, kind: MarkerKind, context: &str, slice: &str) {
+        let (line, column) = line_and_column(self.html_template, self.offset(slice));
+        self.lint.markers.push(MarkerLocation {
+            name: name.to_owned(),
+            kind,
+            context: context.to_owned(),
+            template_name: self.template_name.to_owned(),
+            line,
+            column,
+            dom_path: self.dom_path.clone(),
+        });
+    }
+
+    fn push_error(&mut self, kind: TemplateErrorKind, slice: &str) {
+        let err = TemplateError::new(
+            kind,
+            self.template_name,
+            self.html_template,
+            self.offset(slice),
+            &self.dom_path,
+        );
+        self.lint.errors.push(err);
+    }
+
+    /// The tokens are slices of the html template,
+    /// so the pointer difference is the position in the template.
+    fn offset(&self, slice: &str) -> usize {
+        let offset = (slice.as_ptr() as usize).wrapping_sub(self.html_template.as_ptr() as usize);
+        if offset <= self.html_template.len() {
+            offset
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unwrap::unwrap;
+
+    #[test]
+    fn markers_with_kind_and_position() {
+        let lint = lint_template(
+            "card",
+            "<div data-wt-title=\"wt_title\" title=\"x\">\n<!--wt_name-->name\n<!--wb_show--><b data-on-click=\"wl_pick\">b</b></div>",
+        );
+        assert!(lint.errors.is_empty());
+        let found: Vec<(&str, MarkerKind, usize)> = lint
+            .markers
+            .iter()
+            .map(|marker| (marker.name.as_str(), marker.kind, marker.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("wt_title", MarkerKind::Attribute, 1),
+                ("wt_name", MarkerKind::Text, 2),
+                ("wb_show", MarkerKind::Boolean, 3),
+                ("wl_pick", MarkerKind::Listener, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_markers_are_errors() {
+        let lint = lint_template("card", "<div><!--wt_name--><p></div>");
+        assert!(!lint.errors.is_empty());
+        let lint = lint_template(
+            "card",
+            "<div><!--wb_a--><p>a</p><!--wb_else_if--><p>b</p></div>",
+        );
+        assert!(lint
+            .markers
+            .iter()
+            .all(|marker| marker.name != "wb_else_if"));
+        assert!(matches!(
+            unwrap!(lint.errors.first()).kind,
+            TemplateErrorKind::BadMarkerPrefix { .. }
+        ));
+    }
+
+    #[test]
+    fn rust_names_compared_with_markers() {
+        let rust_code =
+            r#"match fn_name { "wt_name" => x, "wt_unused" => y, "not_a_marker" => z }"#;
+        let known_names = names_in_rust_code(rust_code);
+        assert_eq!(known_names, vec!["wt_name", "wt_unused"]);
+        let lint = lint_template("card", "<div><!--wt_name-->name<!--wt_nmae-->typo</div>");
+        let unknown = unknown_markers(&lint.markers, &known_names);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].name, "wt_nmae");
+        assert_eq!(unused_names(&lint.markers, &known_names), vec!["wt_unused"]);
+        let derive_code = r#"
+    #[wt]
+    fn nick_name(&self) -> String { self.name.clone() }
+    #[wb]
+    #[allow(dead_code)]
+    pub fn wb_show(&self) -> bool { true }
+    #[wl("wl_pick")]
+    fn on_pick(root: &mut dyn RootRender, vdom: VdomWeak, event: web_sys::Event) {}
+"#;
+        assert_eq!(
+            names_in_rust_code(derive_code),
+            vec!["wt_nick_name", "wb_show", "wl_pick"]
+        );
+        assert_eq!(
+            names_in_manifest("# names\nwt_name\n\nwt_name\nwb_show\n"),
+            vec!["wt_name", "wb_show"]
+        );
+        assert_eq!(
+            unwrap!(lint.markers.first()).to_string(),
+            "card:1:10: text <!--wt_name--> in <div>"
+        );
+    }
+}
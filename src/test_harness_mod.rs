@@ -0,0 +1,467 @@
+//! **test_harness_mod**
+//! Renders the template natively into a plain tree for the tests with `cargo test`.
+//! There is no dodrio RenderContext and no browser, so <!--wn_xxx--> calls replace_with_html().
+//! The tree can be queried by tag, attribute and text.
+//! MockTemplating returns the values from its HashMaps and remembers the called markers in order,
+//! the wl_ listeners with their arguments included.
+//! The project can render also its own RootRenderingComponent with render_for_test().
+
+// region: use
+use crate::event_listener_mod::*;
+use crate::html_template_mod::*;
+use crate::render_target_mod::*;
+use crate::template_error_mod::*;
+use dodrio::{Node, RenderContext, RootRender, VdomWeak};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use unwrap::unwrap;
+// endregion: use
+
+/// Renders the html template into the tree for the tests.
+/// The layout and the includes are resolved from the template_source() like in render_template().
+pub fn render_for_test<R: HtmlTemplating + ?Sized>(
+    rrc: &R,
+    html_template: &str,
+) -> Result<TestElement, TemplateError> {
+    let parsed_template = rrc.parse_template(html_template)?;
+    render_to_target(
+        rrc,
+        &parsed_template,
+        HtmlOrSvg::Html,
+        TestTarget::default(),
+    )
+}
+
+/// The child node in the tree for the tests.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestNode {
+    /// element with attributes and children
+    Element(TestElement),
+    /// text, entities already decoded
+    Text(String),
+    /// the html from replace_with_html() for the marker <!--wn_xxx-->
+    Html { fn_name: String, html: String },
+}
+
+/// The rendered element in the tree for the tests.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestElement {
+    pub name: String,
+    pub html_or_svg: HtmlOrSvg,
+    pub attributes: Vec<(String, String)>,
+    /// the value of data-key
+    pub key: Option<String>,
+    /// the wb_ marker that retained this element
+    pub retained_by: Option<String>,
+    /// the event and the fn_name of the listeners, in the order of the template
+    pub listeners: Vec<(String, String)>,
+    pub children: Vec<TestNode>,
+}
+
+impl TestElement {
+    /// the value of the attribute
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(x, _)| x == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// the text of all the descendants joined together
+    pub fn text(&self) -> String {
+        let mut txt = String::new();
+        for child in &self.children {
+            match child {
+                TestNode::Element(element) => txt.push_str(&element.text()),
+                TestNode::Text(child_txt) => txt.push_str(child_txt),
+                TestNode::Html { .. } => {}
+            }
+        }
+        // return
+        txt
+    }
+
+    /// the child elements without the text nodes
+    pub fn child_elements(&self) -> Vec<&TestElement> {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                TestNode::Element(element) => Some(element),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// this element and all the descendants in the document order
+    pub fn descendants(&self) -> Vec<&TestElement> {
+        let mut elements = vec![self];
+        for child in self.child_elements() {
+            elements.extend(child.descendants());
+        }
+        // return
+        elements
+    }
+
+    /// the elements with the tag name, this one included
+    pub fn find_all(&self, name: &str) -> Vec<&TestElement> {
+        self.find_all_by(|element| element.name == name)
+    }
+
+    /// the first element with the tag name, this one included
+    pub fn find(&self, name: &str) -> Option<&TestElement> {
+        self.find_all(name).into_iter().next()
+    }
+
+    /// the elements with the attribute value
+    pub fn find_by_attribute(&self, name: &str, value: &str) -> Vec<&TestElement> {
+        self.find_all_by(|element| element.attribute(name) == Some(value))
+    }
+
+    /// the elements with the direct text child that contains the text
+    pub fn find_by_text(&self, txt: &str) -> Vec<&TestElement> {
+        self.find_all_by(|element| {
+            element
+                .children
+                .iter()
+                .any(|child| matches!(child, TestNode::Text(child_txt) if child_txt.contains(txt)))
+        })
+    }
+
+    /// the elements that satisfy the predicate
+    pub fn find_all_by(&self, predicate: impl Fn(&TestElement) -> bool) -> Vec<&TestElement> {
+        self.descendants()
+            .into_iter()
+            .filter(|element| predicate(element))
+            .collect()
+    }
+}
+
+/// The backend for the tree of TestElement.
+/// The listener methods are called like in dodrio, but the listeners are not attached,
+/// only the event and the fn_name are remembered.
+#[derive(Default)]
+pub struct TestTarget {
+    elements: Vec<TestElement>,
+    root: Option<TestElement>,
+}
+
+impl TestTarget {
+    /// the last opened element
+    fn current(&mut self) -> &mut TestElement {
+        unwrap!(self.elements.last_mut())
+    }
+}
+
+impl RenderTarget for TestTarget {
+    type Node = TestElement;
+
+    fn begin_element(&mut self, name: &str, html_or_svg: HtmlOrSvg) {
+        self.elements.push(TestElement {
+            name: name.to_owned(),
+            html_or_svg,
+            attributes: vec![],
+            key: None,
+            retained_by: None,
+            listeners: vec![],
+            children: vec![],
+        });
+    }
+
+    fn attribute(&mut self, name: &str, value: &str) {
+        self.current()
+            .attributes
+            .push((name.to_owned(), value.to_owned()));
+    }
+
+    fn key(&mut self, key: &str) {
+        self.current().key = Some(key.to_owned());
+    }
+
+    fn retained_by(&mut self, marker: &str) {
+        self.current().retained_by = Some(marker.to_owned());
+    }
+
+    fn listener<R: HtmlTemplating + ?Sized>(&mut self, event: &str, markers: &[ListenerMarker<R>]) {
+        for marker in markers {
+            // the closure is dropped, the call is remembered by MockTemplating
+            let _listener = marker.listener_box();
+            self.current()
+                .listeners
+                .push((event.to_owned(), marker.fn_name.to_owned()));
+        }
+    }
+
+    fn text(&mut self, txt: &str) {
+        self.current().children.push(TestNode::Text(txt.to_owned()));
+    }
+
+    fn splice_children<R: HtmlTemplating + ?Sized>(&mut self, marker: &SpliceMarker<R>) {
+        let html = marker.html();
+        self.current().children.push(TestNode::Html {
+            fn_name: marker.fn_name.to_owned(),
+            html,
+        });
+    }
+
+    fn end_element(&mut self) {
+        let element = unwrap!(self.elements.pop());
+        match self.elements.last_mut() {
+            Some(parent) => parent.children.push(TestNode::Element(element)),
+            None => self.root = Some(element),
+        }
+    }
+
+    fn finish(self) -> Option<TestElement> {
+        self.root
+    }
+}
+
+/// One call of a marker method on MockTemplating.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkerCall {
+    pub fn_name: String,
+    /// the indexes of the repeat items, from the outer to the inner repeat
+    pub repeat: Vec<usize>,
+    /// the arguments of wl_xxx(args), empty for the other markers
+    pub args: Vec<ListenerArg>,
+}
+
+/// The implementor of HtmlTemplating for the tests.
+/// The missing wt_ string is the fn_name itself, the missing wb_ boolean is true
+/// and the missing wr_ repeat count is 0.
+#[derive(Default)]
+pub struct MockTemplating {
+    /// values for wt_ markers
+    pub strings: HashMap<String, String>,
+    /// values for wt_ markers inside repeat, by the index of the inner repeat item
+    pub strings_in_repeat: HashMap<String, Vec<String>>,
+    /// values for wb_ markers
+    pub booleans: HashMap<String, bool>,
+    /// counts for wr_ markers
+    pub repeat_counts: HashMap<String, usize>,
+    /// html for wn_ markers
+    pub html: HashMap<String, String>,
+    calls: RefCell<Vec<MarkerCall>>,
+}
+
+impl MockTemplating {
+    /// all the called markers in order
+    pub fn calls(&self) -> Vec<MarkerCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// the names of the called markers in order
+    pub fn call_names(&self) -> Vec<String> {
+        self.calls
+            .borrow()
+            .iter()
+            .map(|call| call.fn_name.clone())
+            .collect()
+    }
+
+    /// forgets the calls before the next render
+    pub fn clear_calls(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    fn push_call(&self, fn_name: &str, repeat: &[RepeatItem]) {
+        self.push_call_with_args(fn_name, vec![], repeat);
+    }
+
+    fn push_call_with_args(&self, fn_name: &str, args: Vec<ListenerArg>, repeat: &[RepeatItem]) {
+        self.calls.borrow_mut().push(MarkerCall {
+            fn_name: fn_name.to_owned(),
+            repeat: repeat.iter().map(|item| item.index).collect(),
+            args,
+        });
+    }
+}
+
+impl HtmlTemplating for MockTemplating {
+    fn replace_with_string(&self, fn_name: &str) -> String {
+        self.replace_with_string_in_repeat(fn_name, &[])
+    }
+
+    fn retain_next_node_or_attribute<'a>(&self, fn_name: &str) -> bool {
+        self.retain_next_node_or_attribute_in_repeat(fn_name, &[])
+    }
+
+    fn replace_with_nodes<'a>(&self, _cx: &mut RenderContext<'a>, fn_name: &str) -> Vec<Node<'a>> {
+        self.push_call(fn_name, &[]);
+        vec![]
+    }
+
+    fn set_event_listener(
+        &self,
+        fn_name: String,
+    ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static> {
+        self.set_event_listener_in_repeat(fn_name, &[])
+    }
+
+    fn repeat_count(&self, fn_name: &str, repeat: &[RepeatItem]) -> usize {
+        self.push_call(fn_name, repeat);
+        self.repeat_counts.get(fn_name).copied().unwrap_or(0)
+    }
+
+    fn replace_with_string_in_repeat(&self, fn_name: &str, repeat: &[RepeatItem]) -> String {
+        self.push_call(fn_name, repeat);
+        let in_repeat = repeat.last().and_then(|item| {
+            self.strings_in_repeat
+                .get(fn_name)
+                .and_then(|values| values.get(item.index))
+        });
+        // return
+        in_repeat
+            .or_else(|| self.strings.get(fn_name))
+            .cloned()
+            .unwrap_or_else(|| fn_name.to_owned())
+    }
+
+    fn retain_next_node_or_attribute_in_repeat(
+        &self,
+        fn_name: &str,
+        repeat: &[RepeatItem],
+    ) -> bool {
+        self.push_call(fn_name, repeat);
+        self.booleans.get(fn_name).copied().unwrap_or(true)
+    }
+
+    fn set_event_listener_in_repeat(
+        &self,
+        fn_name: String,
+        repeat: &[RepeatItem],
+    ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static> {
+        self.push_call(&fn_name, repeat);
+        Box::new(|_root, _vdom, _event| {})
+    }
+
+    fn set_event_listener_with_args(
+        &self,
+        fn_name: String,
+        args: Vec<ListenerArg>,
+        repeat: &[RepeatItem],
+    ) -> Box<dyn Fn(&mut dyn RootRender, VdomWeak, web_sys::Event) + 'static> {
+        self.push_call_with_args(&fn_name, args, repeat);
+        Box::new(|_root, _vdom, _event| {})
+    }
+
+    fn replace_with_html(&self, fn_name: &str) -> String {
+        self.replace_with_html_in_repeat(fn_name, &[])
+    }
+
+    fn replace_with_html_in_repeat(&self, fn_name: &str, repeat: &[RepeatItem]) -> String {
+        self.push_call(fn_name, repeat);
+        self.html.get(fn_name).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = r#"<div class="{{wt_class}}">
+<p><!--wt_title-->title</p>
+<!--wb_show--><b>shown</b>
+<!--wb_hidden--><i>hidden</i>
+<ul><!--wr_items--><li data-key="{{wt_item}}" data-on-click="wl_pick">{{wt_item}}</li></ul>
+<!--wn_extra--><br />
+</div>"#;
+
+    fn mock() -> MockTemplating {
+        let mut rrc = MockTemplating::default();
+        rrc.strings.insert("wt_class".to_owned(), "card".to_owned());
+        rrc.strings
+            .insert("wt_title".to_owned(), "Hello".to_owned());
+        rrc.strings_in_repeat
+            .insert("wt_item".to_owned(), vec!["a".to_owned(), "b".to_owned()]);
+        rrc.booleans.insert("wb_hidden".to_owned(), false);
+        rrc.repeat_counts.insert("wr_items".to_owned(), 2);
+        rrc.html
+            .insert("wn_extra".to_owned(), "<em>x</em>".to_owned());
+        rrc
+    }
+
+    #[test]
+    fn markers_render_the_tree() {
+        let rrc = mock();
+        let root = unwrap!(render_for_test(&rrc, TEMPLATE));
+        assert_eq!(root.attribute("class"), Some("card"));
+        assert_eq!(unwrap!(root.find("p")).text(), "Hello");
+        assert_eq!(
+            unwrap!(root.find("b")).retained_by.as_deref(),
+            Some("wb_show")
+        );
+        assert!(root.find("i").is_none());
+        let items = root.find_all("li");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].key.as_deref(), Some("b"));
+        assert_eq!(items[1].text(), "b");
+        assert_eq!(
+            items[0].listeners,
+            vec![("click".to_owned(), "wl_pick".to_owned())]
+        );
+        assert!(root.children.contains(&TestNode::Html {
+            fn_name: "wn_extra".to_owned(),
+            html: "<em>x</em>".to_owned(),
+        }));
+        assert!(root.find("br").is_none());
+    }
+
+    #[test]
+    fn markers_are_called_in_order() {
+        let rrc = mock();
+        unwrap!(render_for_test(&rrc, TEMPLATE));
+        assert_eq!(
+            rrc.call_names(),
+            vec![
+                "wt_class",
+                "wt_title",
+                "wb_show",
+                "wb_hidden",
+                "wr_items",
+                "wt_item",
+                "wl_pick",
+                "wt_item",
+                "wt_item",
+                "wl_pick",
+                "wt_item",
+                "wn_extra",
+            ]
+        );
+        let calls = rrc.calls();
+        assert_eq!(calls[5].repeat, vec![0]);
+        assert_eq!(calls[6].repeat, vec![0]);
+        assert_eq!(calls[9].repeat, vec![1]);
+        assert!(calls[11].repeat.is_empty());
+    }
+
+    #[test]
+    fn listener_calls_have_args_and_repeat() {
+        let rrc = mock();
+        let template =
+            r#"<ul><!--wr_items--><li data-on-click.once="wl_pick({{wt_item}}, 17)">x</li></ul>"#;
+        unwrap!(render_for_test(&rrc, template));
+        let picks: Vec<MarkerCall> = rrc
+            .calls()
+            .into_iter()
+            .filter(|call| call.fn_name == "wl_pick")
+            .collect();
+        assert_eq!(picks.len(), 2);
+        assert_eq!(picks[1].repeat, vec![1]);
+        assert_eq!(
+            picks[1].args,
+            vec![ListenerArg::Str("b".to_owned()), ListenerArg::Int(17)]
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_are_an_error() {
+        let mut rrc = mock();
+        rrc.strings_in_repeat
+            .insert("wt_item".to_owned(), vec!["a".to_owned(), "a".to_owned()]);
+        let err = unwrap!(render_for_test(&rrc, TEMPLATE).err());
+        assert_eq!(err.kind, TemplateErrorKind::DuplicateKey("a".to_owned()));
+        // the data-key of the second li
+        assert_eq!((err.line, err.column), (5, 24));
+    }
+}
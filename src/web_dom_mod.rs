@@ -0,0 +1,420 @@
+//! **web_dom_mod**
+//! Renders the template straight into the DOM with web_sys, without the dodrio Vdom.
+//! For static widgets embedded in pages that are not made with dodrio.
+//! The markers are the same, but <!--wn_xxx--> calls replace_with_html() like on the server.
+//! The data-on- listeners are wasm_bindgen Closures, kept alive in WebDomFragment
+//! and removed from the elements when it is dropped.
+//! There is no VdomWeak here, so the widget calls WebDomListener::on_event() instead of
+//! set_event_listener(). The widget has its own scheduler: it renders again in the next animation frame.
+//! The elements are created again on every render, so the widget keeps the state of the modifiers
+//! by listener_state_key(). The states of the listeners that are not rendered again are dropped.
+//! The .debounce timer calls the widget later.
+
+// region: use
+use crate::event_listener_mod::*;
+use crate::html_template_mod::*;
+use crate::parsed_template_mod::*;
+use crate::render_target_mod::*;
+use crate::template_error_mod::*;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use unwrap::unwrap;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+// endregion: use
+
+/// The namespace for create_element_ns() of the html elements.
+pub const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// The event listener attached to the element.
+pub(crate) type DomListener = (web_sys::Element, String, Closure<dyn Fn(web_sys::Event)>);
+
+/// The listeners of the widget. The rrc implements it instead of set_event_listener().
+pub trait WebDomListener {
+    /// Handles the event of the data-on- listener.
+    /// Returns true if the widget must be rendered again.
+    fn on_event(
+        &mut self,
+        fn_name: &str,
+        args: &[ListenerArg],
+        repeat: &[RepeatItem],
+        event: web_sys::Event,
+    ) -> bool;
+}
+
+/// The data-on- listener owned by the DOM closure, after the render is finished.
+#[derive(Clone, Debug)]
+pub struct WebDomListenerCall {
+    /// the fn_name wl_xxx
+    pub fn_name: String,
+    /// the arguments with the placeholders already replaced, empty without arguments
+    pub args: Vec<ListenerArg>,
+    /// the fn_name and index of the repeat items
+    repeat: Vec<(String, usize)>,
+}
+
+impl WebDomListenerCall {
+    /// the repeat items around the element
+    pub fn repeat(&self) -> Vec<RepeatItem<'_>> {
+        self.repeat
+            .iter()
+            .map(|(fn_name, index)| RepeatItem {
+                fn_name,
+                index: *index,
+            })
+            .collect()
+    }
+}
+
+/// The function that receives the events from the DOM closures.
+pub type WebDomDispatch = Rc<dyn Fn(&WebDomListenerCall, web_sys::Event)>;
+
+/// The modifier states of one widget by listener_state_key().
+/// Only the states used in the last render are kept for the next render.
+#[derive(Default)]
+pub struct ModifierStates {
+    previous: HashMap<String, Rc<ModifierState>>,
+    current: HashMap<String, Rc<ModifierState>>,
+}
+
+impl ModifierStates {
+    /// the states of the last render wait for the next render
+    fn next_render(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// the state from the last render or a new one
+    fn state(&mut self, state_key: &str) -> Rc<ModifierState> {
+        let state = match self.previous.remove(state_key) {
+            Some(state) => state,
+            None => self.current.get(state_key).cloned().unwrap_or_default(),
+        };
+        self.current.insert(state_key.to_owned(), state.clone());
+        // return
+        state
+    }
+}
+
+/// The rendered template in the DocumentFragment with its listeners.
+/// Dropping it removes the listeners from the elements.
+pub struct WebDomFragment {
+    /// after append_child() the fragment is empty, the listeners stay on the elements
+    pub fragment: web_sys::DocumentFragment,
+    listeners: Vec<DomListener>,
+    modifier_states: ModifierStates,
+}
+
+impl WebDomFragment {
+    /// the modifier states for the next render
+    pub fn take_modifier_states(&mut self) -> ModifierStates {
+        std::mem::take(&mut self.modifier_states)
+    }
+}
+
+impl Drop for WebDomFragment {
+    fn drop(&mut self) {
+        remove_dom_listeners(&self.listeners);
+    }
+}
+
+/// The backend that creates the DOM nodes with the document.
+/// The listeners call the dispatch function.
+pub struct WebDomTarget {
+    document: web_sys::Document,
+    dispatch: WebDomDispatch,
+    elements: Vec<web_sys::Element>,
+    root: Option<web_sys::Element>,
+    listeners: Vec<DomListener>,
+    modifier_states: ModifierStates,
+}
+
+impl WebDomTarget {
+    /// the dispatch is usually the widget that calls its rrc
+    pub fn new(document: web_sys::Document, dispatch: WebDomDispatch) -> Self {
+        WebDomTarget {
+            document,
+            dispatch,
+            elements: vec![],
+            root: None,
+            listeners: vec![],
+            modifier_states: ModifierStates::default(),
+        }
+    }
+
+    /// keeps the state of .once, .throttle and .debounce from the previous render
+    pub fn with_modifier_states(mut self, mut modifier_states: ModifierStates) -> Self {
+        modifier_states.next_render();
+        self.modifier_states = modifier_states;
+        // return
+        self
+    }
+
+    /// the last opened element
+    fn current(&self) -> &web_sys::Element {
+        unwrap!(self.elements.last())
+    }
+}
+
+impl RenderTarget for WebDomTarget {
+    type Node = WebDomFragment;
+
+    fn begin_element(&mut self, name: &str, html_or_svg: HtmlOrSvg) {
+        let namespace = html_or_svg.namespace_uri().unwrap_or(HTML_NAMESPACE);
+        let element = unwrap!(self.document.create_element_ns(Some(namespace), name));
+        self.elements.push(element);
+    }
+
+    fn attribute(&mut self, name: &str, value: &str) {
+        unwrap!(self.current().set_attribute(name, value));
+    }
+
+    fn listener<R: HtmlTemplating + ?Sized>(&mut self, event: &str, markers: &[ListenerMarker<R>]) {
+        let element = self.current().clone();
+        let listener = add_dom_listener(
+            &element,
+            event,
+            markers,
+            self.dispatch.clone(),
+            &mut self.modifier_states,
+        );
+        self.listeners.push(listener);
+    }
+
+    fn text(&mut self, txt: &str) {
+        let text_node = self.document.create_text_node(txt);
+        unwrap!(self.current().append_child(&text_node));
+    }
+
+    fn splice_children<R: HtmlTemplating + ?Sized>(&mut self, marker: &SpliceMarker<R>) {
+        // the RenderContext for replace_with_nodes() exists only inside dodrio
+        unwrap!(self
+            .current()
+            .insert_adjacent_html("beforeend", &marker.html()));
+    }
+
+    fn end_element(&mut self) {
+        let element = unwrap!(self.elements.pop());
+        match self.elements.last() {
+            Some(parent) => {
+                unwrap!(parent.append_child(&element));
+            }
+            None => self.root = Some(element),
+        }
+    }
+
+    fn finish(self) -> Option<WebDomFragment> {
+        let root = self.root?;
+        let fragment = self.document.create_document_fragment();
+        unwrap!(fragment.append_child(&root));
+        // return
+        Some(WebDomFragment {
+            fragment,
+            listeners: self.listeners,
+            modifier_states: self.modifier_states,
+        })
+    }
+}
+
+/// Adds one DOM listener for all the data-on- listeners of the event.
+/// The modifiers are applied before the dispatch, .debounce dispatches from its timer.
+pub(crate) fn add_dom_listener<R: HtmlTemplating + ?Sized>(
+    element: &web_sys::Element,
+    event: &str,
+    markers: &[ListenerMarker<R>],
+    dispatch: WebDomDispatch,
+    modifier_states: &mut ModifierStates,
+) -> DomListener {
+    let calls: Vec<(WebDomListenerCall, Vec<ListenerModifier>, Rc<ModifierState>)> = markers
+        .iter()
+        .map(|marker| {
+            let call = WebDomListenerCall {
+                fn_name: marker.fn_name.to_owned(),
+                args: marker.args.clone().unwrap_or_default(),
+                repeat: marker
+                    .repeat()
+                    .iter()
+                    .map(|item| (item.fn_name.to_owned(), item.index))
+                    .collect(),
+            };
+            (
+                call,
+                marker.modifiers.to_vec(),
+                modifier_states.state(&marker.state_key),
+            )
+        })
+        .collect();
+    let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        for (call, modifiers, state) in &calls {
+            match state.decide(modifiers, &event) {
+                ModifierDecision::Skip => {}
+                ModifierDecision::Call => dispatch(call, event.clone()),
+                ModifierDecision::Debounce(milliseconds) => {
+                    let dispatch = dispatch.clone();
+                    let call = call.clone();
+                    let event = event.clone();
+                    restart_debounce_timer(state, modifiers, milliseconds, move || {
+                        dispatch(&call, event)
+                    });
+                }
+            }
+        }
+    }) as Box<dyn Fn(web_sys::Event)>);
+    unwrap!(element.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref()));
+    // return
+    (element.clone(), event.to_owned(), closure)
+}
+
+/// removes the listeners from the elements
+pub(crate) fn remove_dom_listeners(listeners: &[DomListener]) {
+    for (element, event, closure) in listeners {
+        let _ =
+            element.remove_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+    }
+}
+
+/// The template rendered into the container element, without the dodrio Vdom.
+pub struct WebDomWidget<R: HtmlTemplating + WebDomListener + 'static> {
+    inner: Rc<WidgetInner<R>>,
+}
+
+struct WidgetInner<R: HtmlTemplating + WebDomListener + 'static> {
+    rrc: Rc<RefCell<R>>,
+    parsed_template: ParsedTemplate,
+    container: web_sys::Element,
+    rendered: RefCell<Option<WebDomFragment>>,
+    // the render is already waiting for the animation frame
+    is_scheduled: Cell<bool>,
+    // for the dispatch of the listeners
+    weak_self: Weak<WidgetInner<R>>,
+}
+
+impl<R: HtmlTemplating + WebDomListener + 'static> WebDomWidget<R> {
+    /// Renders the template into the container and replaces its children.
+    /// The listeners call WebDomListener::on_event() on the rrc.
+    pub fn new(
+        container: &web_sys::Element,
+        rrc: R,
+        html_template: &str,
+    ) -> Result<Self, TemplateError> {
+        let parsed_template = rrc.parse_template(html_template)?;
+        let widget = Self::without_render(container, rrc, parsed_template);
+        widget.inner.render()?;
+        // return
+        Ok(widget)
+    }
+
+    /// The widget before the first render. hydrate() adopts the existing DOM instead.
+    pub(crate) fn without_render(
+        container: &web_sys::Element,
+        rrc: R,
+        parsed_template: ParsedTemplate,
+    ) -> Self {
+        let inner = Rc::new_cyclic(|weak: &Weak<WidgetInner<R>>| WidgetInner {
+            rrc: Rc::new(RefCell::new(rrc)),
+            parsed_template,
+            container: container.clone(),
+            rendered: RefCell::new(None),
+            is_scheduled: Cell::new(false),
+            weak_self: weak.clone(),
+        });
+        // return
+        WebDomWidget { inner }
+    }
+
+    /// the parsed template of the widget
+    pub(crate) fn parsed_template(&self) -> &ParsedTemplate {
+        &self.inner.parsed_template
+    }
+
+    /// the dispatch for the listeners attached to the adopted DOM
+    pub(crate) fn dispatch(&self) -> WebDomDispatch {
+        self.inner.dispatch()
+    }
+
+    /// Keeps the listeners attached to the existing DOM until the next render.
+    pub(crate) fn set_adopted(&self, listeners: Vec<DomListener>, modifier_states: ModifierStates) {
+        let document = unwrap!(self.inner.container.owner_document());
+        self.inner.rendered.replace(Some(WebDomFragment {
+            fragment: document.create_document_fragment(),
+            listeners,
+            modifier_states,
+        }));
+    }
+
+    /// Renders the widget again after the rrc was changed outside of the listeners.
+    pub fn render(&self) -> Result<(), TemplateError> {
+        self.inner.render()
+    }
+
+    /// Renders the widget in the next animation frame, more calls render only once.
+    pub fn schedule_render(&self) {
+        self.inner.schedule_render();
+    }
+
+    /// the root component with the state of the widget
+    pub fn rrc(&self) -> Rc<RefCell<R>> {
+        self.inner.rrc.clone()
+    }
+}
+
+impl<R: HtmlTemplating + WebDomListener + 'static> WidgetInner<R> {
+    /// The old listeners are removed when the old fragment is dropped.
+    fn render(&self) -> Result<(), TemplateError> {
+        let document = unwrap!(self.container.owner_document());
+        let modifier_states = self
+            .rendered
+            .borrow_mut()
+            .as_mut()
+            .map(WebDomFragment::take_modifier_states)
+            .unwrap_or_default();
+        let target =
+            WebDomTarget::new(document, self.dispatch()).with_modifier_states(modifier_states);
+        let rendered = render_to_target(
+            &*self.rrc.borrow(),
+            &self.parsed_template,
+            HtmlOrSvg::Html,
+            target,
+        )?;
+        self.container.set_text_content(None);
+        unwrap!(self.container.append_child(&rendered.fragment));
+        self.rendered.replace(Some(rendered));
+        // return
+        Ok(())
+    }
+
+    /// The render is never inside the listener, because it drops the closure of the listener.
+    fn schedule_render(&self) {
+        if self.is_scheduled.replace(true) {
+            return;
+        }
+        let weak = self.weak_self.clone();
+        let callback = Closure::once_into_js(move || {
+            if let Some(widget) = weak.upgrade() {
+                widget.is_scheduled.set(false);
+                if let Err(err) = widget.render() {
+                    web_sys::console::error_1(&err.to_string().into());
+                }
+            }
+        });
+        let window = unwrap!(web_sys::window());
+        unwrap!(window.request_animation_frame(callback.unchecked_ref()));
+    }
+
+    /// calls the rrc and schedules the render if the rrc asks for it
+    fn dispatch(&self) -> WebDomDispatch {
+        let weak = self.weak_self.clone();
+        Rc::new(move |call: &WebDomListenerCall, event: web_sys::Event| {
+            if let Some(widget) = weak.upgrade() {
+                // an event dispatched from inside the listener does not get the rrc again
+                let render_again = match widget.rrc.try_borrow_mut() {
+                    Ok(mut rrc) => rrc.on_event(&call.fn_name, &call.args, &call.repeat(), event),
+                    Err(_) => false,
+                };
+                if render_again {
+                    widget.schedule_render();
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,88 @@
+//! **codegen_matches_walker**
+//! The code generator must follow the same rules as the walker in render_target_mod.
+//! The same template is rendered by the walker and by the generated code into the dodrio Node
+//! and the nodes are compared. The RenderContext is created natively with the dodrio
+//! feature xxx-unstable-internal-use-only, enabled only in the dev-dependencies.
+//! The generated code is in tests/generated. If codegen changes, the first test shows the new code.
+
+// region: use
+use dodrio::bumpalo::Bump;
+use dodrio::{CachedSet, Node, RenderContext};
+use rust_wasm_dodrio_templating::codegen_mod::*;
+use rust_wasm_dodrio_templating::html_template_mod::*;
+use rust_wasm_dodrio_templating::parsed_template_mod::*;
+use rust_wasm_dodrio_templating::test_harness_mod::*;
+use std::cell::RefCell;
+use unwrap::unwrap;
+// endregion: use
+
+include!("generated/walker_template.rs");
+
+const TEMPLATE: &str = r##"<div class="card" data-wc-active="wb_active">
+<p data-wt-title="wt_t">old</p>
+<p><!--wt_title-->title</p>
+<span title="{{wt_title}}">Hi {{wt_name}}!</span>
+<!--wb_show--><b>shown</b>
+<!--wb_else_if wb_other--><i>other</i>
+<!--wb_else--><u>else</u>
+<ul><!--wr_items--><li data-key="{{wt_item}}" data-on-click="wl_pick">{{wt_item}}</li></ul>
+<svg><use href="#i"></use></svg>
+<!--wn_extra--><br />
+</div>"##;
+
+fn mock() -> MockTemplating {
+    let mut rrc = MockTemplating::default();
+    rrc.strings.insert("wt_t".to_owned(), "new".to_owned());
+    rrc.strings
+        .insert("wt_title".to_owned(), "Title".to_owned());
+    rrc.strings_in_repeat
+        .insert("wt_item".to_owned(), vec!["a".to_owned(), "b".to_owned()]);
+    rrc.booleans.insert("wb_show".to_owned(), false);
+    rrc.booleans.insert("wb_other".to_owned(), false);
+    rrc.repeat_counts.insert("wr_items".to_owned(), 2);
+    rrc
+}
+
+/// the Debug of the node without the addresses of the listener callbacks
+fn describe(node: &Node) -> String {
+    let debug = format!("{:?}", node);
+    let mut description = String::new();
+    let mut rest = debug.as_str();
+    while let Some(start) = rest.find("callback: (") {
+        description.push_str(&rest[..start]);
+        rest = &rest[start..];
+        rest = &rest[unwrap!(rest.find(')')) + 1..];
+    }
+    description.push_str(rest);
+    // return
+    description
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    let code = unwrap!(generate_rust_code(&[("render_walker_template", TEMPLATE)]));
+    assert_eq!(
+        code,
+        include_str!("generated/walker_template.rs"),
+        "write the new generated code into tests/generated/walker_template.rs"
+    );
+}
+
+#[test]
+fn codegen_renders_the_same_node_as_the_walker() {
+    let bump = Bump::new();
+    let cached_set = RefCell::new(CachedSet::default());
+    let mut templates = Default::default();
+    let mut cx = RenderContext::new(&bump, &cached_set, &mut templates);
+
+    let walker_rrc = mock();
+    let parsed_template = unwrap!(ParsedTemplate::parse(TEMPLATE));
+    let walker_node = unwrap!(walker_rrc.render_parsed(&mut cx, &parsed_template, HtmlOrSvg::Html));
+    let codegen_rrc = mock();
+    let codegen_node = unwrap!(render_walker_template(&codegen_rrc, &mut cx));
+
+    assert_eq!(describe(&walker_node), describe(&codegen_node));
+    assert_eq!(walker_rrc.calls(), codegen_rrc.calls());
+    // the data-wt- after the last attribute replaces the text
+    assert!(describe(&walker_node).contains("text: \"new\""));
+}
@@ -0,0 +1,75 @@
+// Generated by rust_wasm_dodrio_templating::codegen_mod.
+// Do not edit. Change the html template and rebuild.
+
+#[allow(unused_mut, unused_variables, unused_assignments, clippy::all)]
+pub fn render_walker_template<'a, R: ::rust_wasm_dodrio_templating::html_template_mod::HtmlTemplating>(
+    rrc: &R,
+    cx: &mut ::rust_wasm_dodrio_templating::dodrio::RenderContext<'a>,
+) -> Result<::rust_wasm_dodrio_templating::dodrio::Node<'a>, ::rust_wasm_dodrio_templating::template_error_mod::TemplateError> {
+    let bump = cx.bump;
+    Ok({
+        let mut element_1 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "div");
+        let mut else_chain_1 = false;
+        let mut toggle_classes_1: Vec<&str> = vec![];
+        let mut class_is_rendered_1 = false;
+        if rrc.retain_next_node_or_attribute("wb_active") { toggle_classes_1.push("active"); }
+        class_is_rendered_1 = true; element_1 = element_1.attr("class", ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::from_str_in(&::rust_wasm_dodrio_templating::html_template_mod::add_classes("card", &toggle_classes_1), bump).into_bump_str());
+        if !class_is_rendered_1 && !toggle_classes_1.is_empty() { element_1 = element_1.attr("class", ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::from_str_in(&::rust_wasm_dodrio_templating::html_template_mod::add_classes("", &toggle_classes_1), bump).into_bump_str()); }
+        element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "p");
+            element_2 = element_2.child(::rust_wasm_dodrio_templating::dodrio::builder::text(::rust_wasm_dodrio_templating::dodrio::bumpalo::format!(in bump, "{}", ::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&rrc.replace_with_string("wt_t"), rrc.entity_decoding())).into_bump_str()));
+            element_2.finish()
+        });
+        element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "p");
+            element_2 = element_2.child(::rust_wasm_dodrio_templating::dodrio::builder::text(::rust_wasm_dodrio_templating::dodrio::bumpalo::format!(in bump, "{}", ::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&rrc.replace_with_string("wt_title"), rrc.entity_decoding())).into_bump_str()));
+            element_2.finish()
+        });
+        element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "span");
+            element_2 = element_2.attr("title", { let mut txt = ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::new_in(bump); txt.push_str(&::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&rrc.replace_with_string("wt_title"), rrc.entity_decoding())); txt.into_bump_str() });
+            element_2 = element_2.child(::rust_wasm_dodrio_templating::dodrio::builder::text({ let mut txt = ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::new_in(bump); txt.push_str("Hi "); txt.push_str(&::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&rrc.replace_with_string("wt_name"), rrc.entity_decoding())); txt.push_str("!"); txt.into_bump_str() }));
+            element_2.finish()
+        });
+        else_chain_1 = rrc.retain_next_node_or_attribute("wb_show"); if else_chain_1 { element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "b");
+            element_2 = element_2.child(::rust_wasm_dodrio_templating::dodrio::builder::text("shown"));
+            element_2.finish()
+        }); }
+        if !else_chain_1 { else_chain_1 = rrc.retain_next_node_or_attribute("wb_other"); if else_chain_1 { element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "i");
+            element_2 = element_2.child(::rust_wasm_dodrio_templating::dodrio::builder::text("other"));
+            element_2.finish()
+        }); } }
+        if !else_chain_1 { element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "u");
+            element_2 = element_2.child(::rust_wasm_dodrio_templating::dodrio::builder::text("else"));
+            element_2.finish()
+        }); }
+        element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "ul");
+            let mut sibling_keys_2 = ::rust_wasm_dodrio_templating::html_template_mod::SiblingKeys::default();
+            for repeat_index_2 in 0..rrc.repeat_count("wr_items", &[]) { element_2 = element_2.child({
+                let mut element_3 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "li");
+                { let key: &str = { let mut txt = ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::new_in(bump); txt.push_str(&::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&rrc.replace_with_string_in_repeat("wt_item", &[::rust_wasm_dodrio_templating::html_template_mod::RepeatItem { fn_name: "wr_items", index: repeat_index_2 }]), rrc.entity_decoding())); txt.into_bump_str() }; sibling_keys_2.insert(key, "ul", "li", Some(&::rust_wasm_dodrio_templating::template_error_mod::TemplatePosition { template_name: "render_walker_template".to_string(), line: 8, column: 24 }))?; element_3 = element_3.key(sibling_keys_2.unique_node_key(key)); }
+                element_3 = element_3.on("click", rrc.set_event_listener_in_repeat("wl_pick".to_string(), &[::rust_wasm_dodrio_templating::html_template_mod::RepeatItem { fn_name: "wr_items", index: repeat_index_2 }]));
+                element_3 = element_3.child(::rust_wasm_dodrio_templating::dodrio::builder::text({ let mut txt = ::rust_wasm_dodrio_templating::dodrio::bumpalo::collections::String::new_in(bump); txt.push_str(&::rust_wasm_dodrio_templating::html_entity_mod::decode_entities(&rrc.replace_with_string_in_repeat("wt_item", &[::rust_wasm_dodrio_templating::html_template_mod::RepeatItem { fn_name: "wr_items", index: repeat_index_2 }]), rrc.entity_decoding())); txt.into_bump_str() }));
+                element_3.finish()
+            }); }
+            element_2.finish()
+        });
+        element_1 = element_1.child({
+            let mut element_2 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "svg");
+            element_2 = element_2.namespace(Some("http://www.w3.org/2000/svg"));
+            element_2 = element_2.child({
+                let mut element_3 = ::rust_wasm_dodrio_templating::dodrio::builder::ElementBuilder::new(bump, "use");
+                element_3 = element_3.namespace(Some("http://www.w3.org/2000/svg"));
+                element_3 = element_3.attr("href", "#i");
+                element_3.finish()
+            });
+            element_2.finish()
+        });
+        for node in rrc.replace_with_nodes(cx, "wn_extra") { element_1 = element_1.child(node); }
+        element_1.finish()
+    })
+}